@@ -1,11 +1,91 @@
-use aoc2024::day19;
-use criterion::{criterion_group, criterion_main, Criterion};
-
-fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("day19::b", |b| {
-        b.iter(|| day19::b(day19::INPUT));
-    });
-}
-
-criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
+use aoc2024::{
+    input::{self, Source},
+    params,
+    registry::{self, Part},
+};
+use criterion::{criterion_group, criterion_main, Criterion, SamplingMode};
+use std::{
+    env,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let source = Source::from_env();
+    let filters = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect::<Vec<_>>();
+
+    for day in registry::DAYS {
+        let input = match input::load(day, &source) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("Skipping {}: {err}", day.name);
+                continue;
+            }
+        };
+
+        let params = match params::resolve(day, &input, &[]) {
+            Ok(params) => params,
+            Err(err) => {
+                eprintln!("Skipping {}: {err}", day.name);
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(day.name);
+
+        if let Some(parse) = day.parse {
+            group.bench_function("parse", |b| b.iter(|| parse(black_box(&input.text))));
+        }
+
+        for part in Part::ALL {
+            if day.wip.contains(&part) {
+                continue;
+            }
+
+            let solver = day.solver(part);
+            let id = format!("{}/{part}", day.name);
+
+            // Slow parts like day21::a would take ages with 100 samples, so they get fewer. Only
+            // probe the parts the filter selects, criterion still does the real filtering.
+            let mut slow = false;
+
+            if filters.is_empty() || filters.iter().any(|f| id.contains(f.as_str())) {
+                let start = Instant::now();
+
+                if let Err(err) = solver(&input.text, &params) {
+                    eprintln!("Skipping {id}: {err}");
+                    continue;
+                }
+
+                slow = start.elapsed() > Duration::from_millis(100);
+            }
+
+            // The settings stay on the group, so the defaults are put back for the next part.
+            if slow {
+                group.sample_size(10).sampling_mode(SamplingMode::Flat);
+            } else {
+                group.sample_size(100).sampling_mode(SamplingMode::Auto);
+            }
+
+            group.bench_function(part.to_string(), |b| {
+                b.iter(|| solver(black_box(&input.text), &params))
+            });
+
+            // The part again on input parsed outside the measured loop, `parse` plus `solve/a`
+            // is roughly `a`.
+            if let Some(solve) = day.solve {
+                group.bench_function(format!("solve/{part}"), |b| {
+                    b.iter_custom(|iters| solve(&input.text, &params, part, iters).unwrap())
+                });
+            }
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
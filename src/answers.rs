@@ -0,0 +1,169 @@
+use crate::{registry::Part, AdventHashMap};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// Used when neither `--answers` nor `AOC_ANSWERS` is set.
+pub const DEFAULT_PATH: &str = "answers.txt";
+
+/// Known answers keyed by day, part and input id, see [`Input::id`](crate::input::Input::id).
+/// Keying by the contents of the input rather than its file name keeps the answers for
+/// everyone's own `inputs/5.txt` apart.
+///
+/// The file has one answer per line, `<day> <part> <input id> <answer>`, for example
+/// `18 b 9c1c2a5b4ef1e2a0 [28, 26]`. Everything after the input id is the answer. Empty lines
+/// and lines starting with `#` are ignored.
+#[derive(Default, Debug)]
+pub struct Answers {
+    answers: AdventHashMap<(u32, Part, String), String>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Verdict<'a> {
+    Pass,
+    Fail { expected: &'a str },
+    Unknown,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(4, ' ');
+
+            let (Some(day), Some(part), Some(input), Some(answer)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(format!(
+                    "line {}: expected '<day> <part> <input id> <answer>'",
+                    line_no + 1
+                ));
+            };
+
+            if !is_id(input) {
+                return Err(format!(
+                    "line {}: invalid input id '{input}', expected 16 hex digits",
+                    line_no + 1
+                ));
+            }
+
+            let day = day
+                .parse::<u32>()
+                .map_err(|_| format!("line {}: invalid day '{day}'", line_no + 1))?;
+
+            let part = match part {
+                "a" => Part::A,
+                "b" => Part::B,
+                _ => return Err(format!("line {}: invalid part '{part}'", line_no + 1)),
+            };
+
+            answers.insert(day, part, input, answer.trim());
+        }
+
+        Ok(answers)
+    }
+
+    /// Loads the answers file, a missing file has no answers.
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text).map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn get(&self, day: u32, part: Part, input: &str) -> Option<&str> {
+        self.answers
+            .get(&(day, part, input.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u32, part: Part, input: &str, answer: &str) {
+        self.answers
+            .insert((day, part, input.to_string()), answer.to_string());
+    }
+
+    pub fn check(&self, day: u32, part: Part, input: &str, answer: &str) -> Verdict<'_> {
+        match self.get(day, part, input) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected },
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Appends an answer to the answers file, leaving the existing lines and comments alone.
+    /// `name` goes in a comment above it, as the id alone doesn't say which input it was.
+    pub fn append(
+        path: &Path,
+        day: u32,
+        part: Part,
+        input: &str,
+        name: &str,
+        answer: &str,
+    ) -> Result<(), String> {
+        if !is_id(input) {
+            return Err(format!("Invalid input id '{input}'"));
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Could not open {}: {e}", path.display()))?;
+
+        writeln!(file, "# {name}\n{day} {part} {input} {answer}")
+            .map_err(|e| format!("Could not write {}: {e}", path.display()))
+    }
+}
+
+/// Whether `s` looks like an [`Input::id`](crate::input::Input::id).
+fn is_id(s: &str) -> bool {
+    s.len() == 16 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[test]
+fn test_answers() {
+    let answers = Answers::parse(
+        "# comment\n1 a 00000000000000a1 1938424\n\n18 b 0000000000000018 [28, 26]\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        answers.get(18, Part::B, "0000000000000018"),
+        Some("[28, 26]")
+    );
+    assert_eq!(
+        answers.check(1, Part::A, "00000000000000a1", "1938424"),
+        Verdict::Pass
+    );
+    assert_eq!(
+        answers.check(1, Part::A, "00000000000000a1", "11"),
+        Verdict::Fail {
+            expected: "1938424"
+        }
+    );
+    assert_eq!(
+        answers.check(1, Part::B, "00000000000000a1", "31"),
+        Verdict::Unknown
+    );
+    assert_eq!(
+        answers.check(1, Part::A, "00000000000a11ce", "11"),
+        Verdict::Unknown
+    );
+
+    assert!(Answers::parse("1 c 00000000000000a1 0").is_err());
+    assert!(Answers::parse("1 a 00000000000000a1").is_err());
+    assert_eq!(
+        Answers::parse("1 a 1.txt 1938424").unwrap_err(),
+        "line 1: invalid input id '1.txt', expected 16 hex digits"
+    );
+}
@@ -0,0 +1,252 @@
+use crate::{registry::Part, timing::Timing, AdventHashMap};
+use std::{fmt::Write, fs, io, path::Path, time::Duration};
+
+/// Used when neither `--baseline` nor `AOC_BASELINE` is set.
+pub const DEFAULT_PATH: &str = "baseline.txt";
+
+/// Median times to compare later runs against, keyed by day, part and input name.
+///
+/// The file has one time per line, `<day> <part> <input name> <median ns>`, for example
+/// `14 b 14.txt 1530210`. Empty lines and lines starting with `#` are ignored.
+#[derive(Default, Debug)]
+pub struct Baseline {
+    times: AdventHashMap<(u32, Part, String), Duration>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    Unchanged,
+    New,
+}
+
+pub struct Comparison {
+    pub name: String,
+    pub input: String,
+    pub baseline: Option<Duration>,
+    pub current: Duration,
+    pub verdict: Verdict,
+}
+
+impl Comparison {
+    /// The change from the baseline in percent.
+    pub fn change(&self) -> Option<f64> {
+        self.baseline
+            .map(|b| (self.current.as_secs_f64() / b.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+impl Baseline {
+    pub fn parse(text: &str) -> Result<Baseline, String> {
+        let mut baseline = Baseline::default();
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split(' ').collect::<Vec<_>>();
+
+            let [day, part, input, median] = fields[..] else {
+                return Err(format!(
+                    "line {}: expected '<day> <part> <input> <median ns>'",
+                    line_no + 1
+                ));
+            };
+
+            let day = day
+                .parse::<u32>()
+                .map_err(|_| format!("line {}: invalid day '{day}'", line_no + 1))?;
+
+            let part = match part {
+                "a" => Part::A,
+                "b" => Part::B,
+                _ => return Err(format!("line {}: invalid part '{part}'", line_no + 1)),
+            };
+
+            let median = median
+                .parse::<u64>()
+                .map_err(|_| format!("line {}: invalid time '{median}'", line_no + 1))?;
+
+            baseline.insert(day, part, input, Duration::from_nanos(median));
+        }
+
+        Ok(baseline)
+    }
+
+    /// Loads the baseline file, a missing file has no times.
+    pub fn load(path: &Path) -> Result<Baseline, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Baseline::parse(&text).map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn get(&self, day: u32, part: Part, input: &str) -> Option<Duration> {
+        self.times.get(&(day, part, input.to_string())).copied()
+    }
+
+    pub fn insert(&mut self, day: u32, part: Part, input: &str, median: Duration) {
+        self.times.insert((day, part, input.to_string()), median);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut times = self.times.iter().collect::<Vec<_>>();
+        times.sort();
+
+        let mut out = String::from("# <day> <part> <input> <median ns>\n");
+
+        for ((day, part, input), median) in times {
+            writeln!(out, "{day} {part} {input} {}", median.as_nanos()).unwrap();
+        }
+
+        out
+    }
+
+    /// Adds the timings to the baseline file, replacing the times of the same parts and keeping
+    /// the others.
+    pub fn save(path: &Path, timings: &[Timing]) -> Result<(), String> {
+        let mut baseline = Baseline::load(path)?;
+
+        for t in timings {
+            baseline.insert(t.day, t.part, &t.input, t.stats.median);
+        }
+
+        fs::write(path, baseline.to_text())
+            .map_err(|e| format!("Could not write {}: {e}", path.display()))
+    }
+
+    /// Compares the median times with the baseline, a part regressed if it got more than
+    /// `threshold` percent slower.
+    pub fn compare(&self, timings: &[Timing], threshold: f64) -> Vec<Comparison> {
+        timings
+            .iter()
+            .map(|t| {
+                let baseline = self.get(t.day, t.part, &t.input);
+                let current = t.stats.median;
+
+                let limit = 1.0 + threshold / 100.0;
+                let verdict = match baseline.map(|b| current.as_secs_f64() / b.as_secs_f64()) {
+                    None => Verdict::New,
+                    Some(ratio) if ratio > limit => Verdict::Regressed,
+                    Some(ratio) if ratio < 1.0 / limit => Verdict::Improved,
+                    Some(_) => Verdict::Unchanged,
+                };
+
+                Comparison {
+                    name: t.name.clone(),
+                    input: t.input.clone(),
+                    baseline,
+                    current,
+                    verdict,
+                }
+            })
+            .collect()
+    }
+}
+
+fn us(d: Duration) -> String {
+    format!("{:.1}", d.as_secs_f64() * 1e6)
+}
+
+pub fn report(comparisons: &[Comparison]) -> String {
+    let name_width = comparisons.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let input_width = comparisons
+        .iter()
+        .map(|c| c.input.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "{:name_width$}  {:input_width$}  {:>12}  {:>12}  {:>8}",
+        "", "input", "baseline us", "median us", "change"
+    )
+    .unwrap();
+
+    for c in comparisons {
+        let baseline = c.baseline.map_or("-".to_string(), us);
+        let change = c.change().map_or("-".to_string(), |c| format!("{c:+.1}%"));
+        let verdict = match c.verdict {
+            Verdict::Regressed => "REGRESSED",
+            Verdict::Improved => "faster",
+            Verdict::Unchanged => "",
+            Verdict::New => "new",
+        };
+
+        let line = format!(
+            "{:name_width$}  {:input_width$}  {:>12}  {:>12}  {:>8}  {verdict}",
+            c.name,
+            c.input,
+            baseline,
+            us(c.current),
+            change
+        );
+
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+
+    let regressions = comparisons
+        .iter()
+        .filter(|c| c.verdict == Verdict::Regressed)
+        .count();
+
+    writeln!(out, "{regressions} regression(s)").unwrap();
+
+    out
+}
+
+#[test]
+fn test_baseline() {
+    use crate::timing::Stats;
+
+    let baseline = Baseline::parse("# comment\n14 b 14.txt 1000\n\n6 b 6.txt 2000\n").unwrap();
+
+    assert_eq!(
+        baseline.get(14, Part::B, "14.txt"),
+        Some(Duration::from_nanos(1000))
+    );
+    assert_eq!(
+        Baseline::parse(&baseline.to_text()).unwrap().times,
+        baseline.times
+    );
+    assert!(Baseline::parse("14 c 14.txt 1000").is_err());
+    assert!(Baseline::parse("14 b 14.txt").is_err());
+
+    let timing = |day, part, nanos| Timing {
+        day,
+        part,
+        name: format!("day{day:02}::{part}"),
+        input: format!("{day}.txt"),
+        answer: String::new(),
+        stats: Stats::from_samples(&mut [Duration::from_nanos(nanos)]),
+        alloc: None,
+        spans: Vec::new(),
+    };
+
+    let comparisons = baseline.compare(
+        &[
+            timing(14, Part::B, 1200),
+            timing(6, Part::B, 2100),
+            timing(6, Part::A, 10),
+        ],
+        10.0,
+    );
+
+    assert_eq!(
+        comparisons.iter().map(|c| c.verdict).collect::<Vec<_>>(),
+        [Verdict::Regressed, Verdict::Unchanged, Verdict::New]
+    );
+    assert!(report(&comparisons).contains("+20.0%  REGRESSED"));
+}
@@ -1,92 +1,31 @@
+use aoc2024::{
+    day14::Robots,
+    day14_simd,
+    error::{AocError, Result},
+    grid::Grid,
+    input::{self, Source},
+    params, registry,
+    render::{Frame, Rgb},
+};
 use crossterm::{
     event::{read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     execute, terminal,
 };
 use glam::{ivec2, IVec2};
-use std::{fmt::Display, io::stdout, str};
-
-pub static INPUT: &str = include_str!("../input/14.txt");
+use std::{io::stdout, process::ExitCode};
 
-#[derive(Clone)]
-struct Map {
-    data: Vec<u8>,
-    width: i32,
-    height: i32,
-}
-
-impl Map {
-    fn empty(width: i32, height: i32) -> Map {
-        Map {
-            data: vec![b'.'; (width * height) as usize],
-            width,
-            height,
-        }
-    }
-
-    fn set(&mut self, pos: IVec2, new: u8) -> bool {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return false;
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return false;
-        }
-
-        self.data[index as usize] = new;
-
-        true
-    }
-}
-
-impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for line in self.data.chunks(self.width as usize) {
-            writeln!(f, "{}", str::from_utf8(line).unwrap())?;
-        }
-
-        Ok(())
-    }
-}
-
-#[derive(Default, Debug)]
-struct Robots {
-    pos_x: Vec<i8>,
-    pos_y: Vec<i8>,
-    speed_x: Vec<i8>,
-    speed_y: Vec<i8>,
-}
-
-pub fn b(input: &str, size: IVec2) {
-    let mut robots = Robots::default();
-
-    for line in input.lines() {
-        let (left, right) = line.split_once(' ').unwrap();
-
-        let (x, y) = left.split_once(',').unwrap();
-        let x = x.trim_start_matches("p=").parse().unwrap();
-        let y = y.parse().unwrap();
-
-        let (dx, dy) = right.split_once(',').unwrap();
-        let dx = dx.trim_start_matches("v=").parse().unwrap();
-        let dy = dy.parse().unwrap();
-
-        robots.pos_x.push(x);
-        robots.pos_y.push(y);
-        robots.speed_x.push(dx);
-        robots.speed_y.push(dy);
-    }
+pub fn b(input: &str, size: IVec2) -> Result<()> {
+    let mut robots = Robots::parse(input)?;
+    robots.check_room(size)?;
 
     let mut steps: i32 = 0;
-    let mut step: i32 = 7858;
+    // Starts at the answer, then steps by however much `p` and `m` set.
+    let mut step = day14_simd::b(input, size).unwrap_or(0);
     let mut stdout = stdout();
 
     'next: loop {
         execute!(stdout, terminal::Clear(terminal::ClearType::All)).ok();
 
-        let mut map = Map::empty(size.x, size.y);
-
         for _ in 0..step.abs() {
             for i in 0..robots.pos_x.len() {
                 let x = (robots.pos_x[i] as i16 + step.signum() as i16 * robots.speed_x[i] as i16)
@@ -101,43 +40,42 @@ pub fn b(input: &str, size: IVec2) {
 
         steps += step;
 
-        for i in 0..robots.pos_x.len() {
-            let x = robots.pos_x[i];
-            let y = robots.pos_y[i];
+        let robots_pos =
+            (0..robots.pos_x.len()).map(|i| ivec2(robots.pos_x[i] as _, robots.pos_y[i] as _));
 
-            map.set(ivec2(x as _, y as _), b'x');
-        }
-
-        println!("{}", map);
+        Frame::chars(&Grid::new(size.x, size.y, b'.'))
+            .with_highlight(robots_pos, b'x', Rgb::GREEN)
+            .write_terminal(&mut stdout)
+            .ok();
         println!("{steps}");
 
         loop {
-            match read() {
-                Ok(Event::Key(KeyEvent {
+            match read().map_err(|err| AocError::new(format!("could not read a key: {err}")))? {
+                Event::Key(KeyEvent {
                     code: KeyCode::Char(' '),
                     modifiers: KeyModifiers::NONE,
                     kind: KeyEventKind::Press,
                     ..
-                })) => {
+                }) => {
                     continue 'next;
                 }
 
-                Ok(Event::Key(KeyEvent {
+                Event::Key(KeyEvent {
                     code: KeyCode::Char('p'),
                     modifiers: KeyModifiers::NONE,
                     kind: KeyEventKind::Press,
                     ..
-                })) => {
+                }) => {
                     step += 1;
                     println!("{step}");
                 }
 
-                Ok(Event::Key(KeyEvent {
+                Event::Key(KeyEvent {
                     code: KeyCode::Char('m'),
                     modifiers: KeyModifiers::NONE,
                     kind: KeyEventKind::Press,
                     ..
-                })) => {
+                }) => {
                     step -= 1;
                     println!("{step}");
                 }
@@ -148,6 +86,23 @@ pub fn b(input: &str, size: IVec2) {
     }
 }
 
-fn main() {
-    b(INPUT, ivec2(101, 103));
+fn main() -> ExitCode {
+    let day = registry::get(14).unwrap();
+
+    // The same params as `aoc2024 run 14b`, including a params file next to the input.
+    let res = input::load(day, &Source::from_env())
+        .and_then(|input| Ok((params::resolve(day, &input, &[])?, input)))
+        .map_err(AocError::new)
+        .and_then(|(params, input)| {
+            let size = ivec2(params.get("width") as i32, params.get("height") as i32);
+            b(&input.text, size)
+        });
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("day14_b {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
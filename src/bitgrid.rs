@@ -0,0 +1,272 @@
+use glam::{ivec2, IVec2};
+
+/// A 2D grid of bits stored row by row, 64 cells to a word. Positions outside the grid are
+/// never set.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl BitGrid {
+    pub fn new(width: i32, height: i32) -> BitGrid {
+        BitGrid {
+            words: vec![0; ((width * height) as usize).div_ceil(64)],
+            width,
+            height,
+        }
+    }
+
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| (pos.x + pos.y * self.width) as usize)
+    }
+
+    pub fn get(&self, pos: IVec2) -> bool {
+        self.index(pos)
+            .is_some_and(|i| self.words[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    /// Sets the bit at `pos`, returns true if it wasn't set before. Does nothing outside the
+    /// grid.
+    pub fn insert(&mut self, pos: IVec2) -> bool {
+        let Some(i) = self.index(pos) else {
+            return false;
+        };
+
+        let word = &mut self.words[i / 64];
+        let bit = 1 << (i % 64);
+        let new = *word & bit == 0;
+        *word |= bit;
+        new
+    }
+
+    /// Clears the bit at `pos`, returns true if it was set.
+    pub fn remove(&mut self, pos: IVec2) -> bool {
+        let Some(i) = self.index(pos) else {
+            return false;
+        };
+
+        let word = &mut self.words[i / 64];
+        let bit = 1 << (i % 64);
+        let was_set = *word & bit != 0;
+        *word &= !bit;
+        was_set
+    }
+
+    pub fn set(&mut self, pos: IVec2, value: bool) {
+        if value {
+            self.insert(pos);
+        } else {
+            self.remove(pos);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The number of set bits.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Sets every bit set in `other`, which has to be the same size.
+    pub fn union_with(&mut self, other: &BitGrid) {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Clears every bit not set in `other`, which has to be the same size.
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    /// The indices of the set bits, skipping empty words.
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+
+    /// The positions of the set bits, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = IVec2> + '_ {
+        let width = self.width as usize;
+        self.ones()
+            .map(move |i| ivec2((i % width) as i32, (i / width) as i32))
+    }
+}
+
+/// A 2D grid with four bits per cell, one for each direction something passed through it in.
+/// Directions are numbered `0..4`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DirBitGrid {
+    /// Four bits in a row for each cell.
+    bits: BitGrid,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl DirBitGrid {
+    pub fn new(width: i32, height: i32) -> DirBitGrid {
+        DirBitGrid {
+            bits: BitGrid::new(width * 4, height),
+            width,
+            height,
+        }
+    }
+
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height
+    }
+
+    fn bit_pos(pos: IVec2, dir: usize) -> IVec2 {
+        debug_assert!(dir < 4);
+        ivec2(pos.x * 4 + dir as i32, pos.y)
+    }
+
+    pub fn contains(&self, pos: IVec2, dir: usize) -> bool {
+        self.in_bounds(pos) && self.bits.get(Self::bit_pos(pos, dir))
+    }
+
+    /// Sets the bit for `dir` at `pos`, returns true if it wasn't set before. Does nothing
+    /// outside the grid.
+    pub fn insert(&mut self, pos: IVec2, dir: usize) -> bool {
+        self.in_bounds(pos) && self.bits.insert(Self::bit_pos(pos, dir))
+    }
+
+    /// The four bits of the cell at `pos`, bit `dir` for each direction, 0 outside the grid.
+    pub fn get(&self, pos: IVec2) -> u8 {
+        match self.bits.index(Self::bit_pos(pos, 0)) {
+            Some(i) if self.in_bounds(pos) => (self.bits.words[i / 64] >> (i % 64)) as u8 & 0xf,
+            _ => 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// The number of set bits, counting every direction.
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    /// The number of cells with any bit set.
+    pub fn count_cells(&self) -> usize {
+        self.bits
+            .words
+            .iter()
+            .map(|&w| {
+                // Folds each nibble onto its lowest bit.
+                let w = w | (w >> 1);
+                let w = w | (w >> 2);
+                (w & 0x1111_1111_1111_1111).count_ones() as usize
+            })
+            .sum()
+    }
+
+    pub fn union_with(&mut self, other: &DirBitGrid) {
+        self.bits.union_with(&other.bits);
+    }
+
+    pub fn intersect_with(&mut self, other: &DirBitGrid) {
+        self.bits.intersect_with(&other.bits);
+    }
+
+    /// The positions of the cells with any bit set, with their bits, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, u8)> + '_ {
+        let mut last = None;
+
+        self.bits.ones().filter_map(move |i| {
+            let cell = i / 4;
+
+            (last != Some(cell)).then(|| {
+                last = Some(cell);
+                let pos = ivec2(
+                    (cell % self.width as usize) as i32,
+                    (cell / self.width as usize) as i32,
+                );
+                (pos, self.get(pos))
+            })
+        })
+    }
+}
+
+#[test]
+fn test_bit_grid() {
+    let mut grid = BitGrid::new(10, 10);
+
+    assert!(grid.insert(ivec2(3, 0)));
+    assert!(!grid.insert(ivec2(3, 0)));
+    assert!(!grid.insert(ivec2(10, 0)));
+    assert!(grid.insert(ivec2(9, 9)));
+    assert!(grid.insert(ivec2(4, 6)));
+
+    assert!(grid.get(ivec2(4, 6)));
+    assert!(!grid.get(ivec2(-1, 0)));
+    assert_eq!(grid.count(), 3);
+    assert_eq!(
+        grid.iter().collect::<Vec<_>>(),
+        [ivec2(3, 0), ivec2(4, 6), ivec2(9, 9)]
+    );
+
+    let mut other = BitGrid::new(10, 10);
+    other.insert(ivec2(4, 6));
+    other.insert(ivec2(0, 1));
+
+    let mut union = grid.clone();
+    union.union_with(&other);
+    assert_eq!(union.count(), 4);
+
+    grid.intersect_with(&other);
+    assert_eq!(grid.iter().collect::<Vec<_>>(), [ivec2(4, 6)]);
+
+    assert!(grid.remove(ivec2(4, 6)));
+    assert!(!grid.remove(ivec2(4, 6)));
+
+    union.clear();
+    assert_eq!(union.count(), 0);
+}
+
+#[test]
+fn test_dir_bit_grid() {
+    let mut grid = DirBitGrid::new(5, 5);
+
+    assert!(grid.insert(ivec2(1, 2), 0));
+    assert!(grid.insert(ivec2(1, 2), 3));
+    assert!(!grid.insert(ivec2(1, 2), 3));
+    assert!(grid.insert(ivec2(4, 4), 1));
+    assert!(!grid.insert(ivec2(5, 0), 1));
+
+    assert!(grid.contains(ivec2(1, 2), 3));
+    assert!(!grid.contains(ivec2(1, 2), 1));
+    assert_eq!(grid.get(ivec2(1, 2)), 0b1001);
+    assert_eq!(grid.get(ivec2(2, 1)), 0);
+    assert_eq!(grid.get(ivec2(-1, 2)), 0);
+    assert_eq!(grid.count(), 3);
+    assert_eq!(grid.count_cells(), 2);
+    assert_eq!(
+        grid.iter().collect::<Vec<_>>(),
+        [(ivec2(1, 2), 0b1001), (ivec2(4, 4), 0b0010)]
+    );
+}
@@ -0,0 +1,183 @@
+use crate::AdventHashMap;
+use std::hash::Hash;
+
+/// Where the states from repeatedly stepping a start state start repeating: the states at
+/// steps `tail..tail + length` repeat forever after the first `tail` steps.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub tail: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`, at most `tail + length - 1`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm, keeps two states at a time and usually steps less than [`floyd`].
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Finds the length by moving the hare ahead in powers of two, with the tortoise waiting at
+    // the start of each power.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare `length` steps ahead they meet at the start of the cycle.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut tail = 0;
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+/// Floyd's tortoise and hare, stores two states at a time.
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut tail = 0;
+    tortoise = start;
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+/// Remembers every state with the step it was first seen at, steps each state once.
+pub fn hashed<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut seen = AdventHashMap::default();
+    let mut state = start;
+
+    for n in 0.. {
+        let next = step(&state);
+
+        if let Some(first) = seen.insert(state, n) {
+            return Cycle {
+                tail: first,
+                length: n - first,
+            };
+        }
+
+        state = next;
+    }
+
+    unreachable!()
+}
+
+/// The state after `n` steps, without stepping further than into the second time round the
+/// cycle.
+pub fn nth<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut seen = AdventHashMap::default();
+    let mut states = Vec::new();
+    let mut state = start;
+
+    for i in 0..n {
+        if let Some(&first) = seen.get(&state) {
+            let cycle = Cycle {
+                tail: first,
+                length: i - first,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+
+        let next = step(&state);
+        seen.insert(state.clone(), i);
+        states.push(state);
+        state = next;
+    }
+
+    state
+}
+
+/// The period of two independent cycles running side by side.
+pub fn lcm(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+
+    a / x * b
+}
+
+#[test]
+fn test_cycle() {
+    // 3 -> 9 -> 27 -> 81 -> 43 -> 29 -> 87 -> 61 -> 83 -> 49 -> 47 -> 41 -> 23 -> 69 -> 7 -> 21
+    // -> 63 -> 89 -> 67 -> 1 -> 3 mod 100: a cycle of length 20, with 1000 in front of it.
+    let step = |&n: &u32| if n >= 1000 { n - 1000 + 3 } else { n * 3 % 100 };
+    let expected = Cycle {
+        tail: 0,
+        length: 20,
+    };
+
+    assert_eq!(brent(3, step), expected);
+    assert_eq!(floyd(3, step), expected);
+    assert_eq!(hashed(3, step), expected);
+
+    let expected = Cycle {
+        tail: 1,
+        length: 20,
+    };
+
+    assert_eq!(brent(1000, step), expected);
+    assert_eq!(floyd(1000, step), expected);
+    assert_eq!(hashed(1000, step), expected);
+
+    assert_eq!(expected.reduce(0), 0);
+    assert_eq!(expected.reduce(1), 1);
+    assert_eq!(expected.reduce(41), 1);
+
+    assert_eq!(nth(1000, step, 0), 1000);
+    assert_eq!(nth(1000, step, 2), 9);
+    assert_eq!(nth(1000, step, 1_000_000_002), 9);
+
+    assert_eq!(lcm(101, 103), 10403);
+    assert_eq!(lcm(4, 6), 12);
+}
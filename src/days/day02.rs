@@ -1,14 +1,17 @@
+use crate::error::{self, Result};
+
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/2.txt");
 pub static TEST_INPUT: &str = include_str!("../input/2_test.txt");
 
-pub fn a(input: &str) -> i32 {
+pub fn a(input: &str) -> Result<i32> {
     let mut reports = Vec::new();
 
     for line in input.lines() {
         let levels = line
             .split(" ")
-            .map(|n| n.parse::<i32>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|n| error::parse::<i32>(input, n))
+            .collect::<Result<Vec<_>>>()?;
 
         reports.push(levels);
     }
@@ -33,23 +36,24 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    safe_reports
+    Ok(safe_reports)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 2);
-    assert_eq!(a(INPUT), 220);
+    assert_eq!(a(TEST_INPUT).unwrap(), 2);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 220);
 }
 
-pub fn b(input: &str) -> i32 {
+pub fn b(input: &str) -> Result<i32> {
     let mut reports = Vec::new();
 
     for line in input.lines() {
         let levels = line
             .split(" ")
-            .map(|n| n.parse::<i32>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|n| error::parse::<i32>(input, n))
+            .collect::<Result<Vec<_>>>()?;
 
         reports.push(levels);
     }
@@ -80,11 +84,12 @@ pub fn b(input: &str) -> i32 {
         }
     }
 
-    safe_reports
+    Ok(safe_reports)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 4);
-    assert_eq!(b(INPUT), 296);
+    assert_eq!(b(TEST_INPUT).unwrap(), 4);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 296);
 }
@@ -1,148 +1,59 @@
+use crate::{dir::Dir8, error::Result, grid::Grid};
+use glam::IVec2;
+
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/4.txt");
 pub static TEST_INPUT: &str = include_str!("../input/4_test.txt");
 
-struct Map<'a> {
-    data: &'a [u8],
-    stride: i32,
-}
-
-impl Map<'_> {
-    fn get(&self, x: i32, y: i32) -> u8 {
-        let index = x + y * self.stride;
-
-        if x < 0 || x >= self.stride {
-            return b'.';
-        }
-
-        if y < 0 {
-            return b'.';
-        }
-
-        if index >= self.data.len() as i32 || index < 0 {
-            return b'.';
-        }
-
-        self.data[index as usize]
-    }
-}
-
-pub fn a(input: &str) -> i32 {
-    let mut map = Map {
-        data: input.as_bytes(),
-        stride: 0,
-    };
-
-    let mut lines: i32 = 0;
-
-    for line in input.lines() {
-        map.stride = line.len() as i32 + 1;
-        lines += 1;
-    }
+pub fn a(input: &str) -> Result<i32> {
+    let map = Grid::parse(input)?;
 
     let mut count = 0;
 
-    for y in 0..lines {
-        for x in 0..map.stride {
-            let first = map.get(x, y);
-
-            if first == b'X' {
-                let candiadtes_coords: [[(i32, i32); 3]; 4] = [
-                    [(0, 1), (0, 2), (0, 3)],
-                    [(1, 0), (2, 0), (3, 0)],
-                    [(1, -1), (2, -2), (3, -3)],
-                    [(1, 1), (2, 2), (3, 3)],
-                ];
-
-                for coords in candiadtes_coords {
-                    let candidate = [
-                        map.get(x + coords[0].0, y + coords[0].1),
-                        map.get(x + coords[1].0, y + coords[1].1),
-                        map.get(x + coords[2].0, y + coords[2].1),
-                    ];
-
-                    if &candidate[..] == b"MAS" {
-                        count += 1;
-                    }
-                }
-            }
-
-            if first == b'S' {
-                let candiadtes_coords: [[(i32, i32); 3]; 4] = [
-                    [(0, 1), (0, 2), (0, 3)],
-                    [(1, 0), (2, 0), (3, 0)],
-                    [(1, -1), (2, -2), (3, -3)],
-                    [(1, 1), (2, 2), (3, 3)],
-                ];
-
-                for coords in candiadtes_coords {
-                    let candidate = [
-                        map.get(x + coords[0].0, y + coords[0].1),
-                        map.get(x + coords[1].0, y + coords[1].1),
-                        map.get(x + coords[2].0, y + coords[2].1),
-                    ];
+    for pos in map.positions() {
+        if map.get(pos) != b'X' {
+            continue;
+        }
 
-                    if &candidate[..] == b"AMX" {
-                        count += 1;
-                    }
-                }
+        for dir in Dir8::ALL {
+            if (1..4).all(|i| map.get(pos + i * dir.offset()) == b"XMAS"[i as usize]) {
+                count += 1;
             }
         }
     }
 
-    count
+    Ok(count)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 18);
-    assert_eq!(a(INPUT), 2500);
+    assert_eq!(a(TEST_INPUT).unwrap(), 18);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 2500);
 }
 
-pub fn b(input: &str) -> i32 {
-    let mut map = Map {
-        data: input.as_bytes(),
-        stride: 0,
-    };
-
-    let mut lines: i32 = 0;
-
-    for line in input.lines() {
-        map.stride = line.len() as i32 + 1;
-        lines += 1;
-    }
+pub fn b(input: &str) -> Result<i32> {
+    let map = Grid::parse(input)?;
 
-    let mut count = 0;
-
-    for y in 0..lines {
-        for x in 0..map.stride {
-            let c1 = [(0, 2), (1, 1), (2, 0)];
-            let c2 = [(0, 0), (1, 1), (2, 2)];
-
-            let candidate_1 = [
-                map.get(x + c1[0].0, y + c1[0].1),
-                map.get(x + c1[1].0, y + c1[1].1),
-                map.get(x + c1[2].0, y + c1[2].1),
-            ];
-
-            let candidate_2 = [
-                map.get(x + c2[0].0, y + c2[0].1),
-                map.get(x + c2[1].0, y + c2[1].1),
-                map.get(x + c2[2].0, y + c2[2].1),
-            ];
+    // Both diagonals through the `A` have an `M` at one end and an `S` at the other.
+    let is_mas = |pos: IVec2, dir: Dir8| {
+        let ends = [map.get(pos + dir.offset()), map.get(pos - dir.offset())];
+        &ends == b"MS" || &ends == b"SM"
+    };
 
-            if (&candidate_1[..] == b"MAS" || &candidate_1[..] == b"SAM")
-                && (&candidate_2[..] == b"MAS" || &candidate_2[..] == b"SAM")
-            {
-                count += 1;
-            }
-        }
-    }
+    let count = map
+        .positions()
+        .filter(|&pos| {
+            map.get(pos) == b'A' && is_mas(pos, Dir8::UpLeft) && is_mas(pos, Dir8::UpRight)
+        })
+        .count();
 
-    count
+    Ok(count as i32)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 9);
-    assert_eq!(b(INPUT), 1933);
+    assert_eq!(b(TEST_INPUT).unwrap(), 9);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 1933);
 }
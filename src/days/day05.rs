@@ -1,20 +1,34 @@
-use bit_vec::BitVec;
+use crate::{
+    bitgrid::BitGrid,
+    error::{self, AocError, Result},
+    parse,
+};
+use glam::ivec2;
 use std::cmp::Ordering;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/5.txt");
 pub static TEST_INPUT: &str = include_str!("../input/5_test.txt");
 
-pub fn a(input: &str) -> i32 {
-    let (rules_raw, updates_raw) = input.split_once("\n\n").unwrap();
+fn parse_page(input: &str, s: &str) -> Result<i32> {
+    let page = error::parse::<usize>(input, s)?;
 
-    let mut rules = BitVec::from_elem(65535, false);
+    if page >= 255 {
+        return Err(AocError::at(input, s, "expected a page number below 255"));
+    }
+
+    Ok(page as i32)
+}
+
+pub(crate) fn parse(input: &str) -> Result<(BitGrid, Vec<Vec<i32>>)> {
+    let (rules_raw, updates_raw) = parse::two_sections(input)?;
+
+    // A bit at (a, b) for each rule that page a goes before page b.
+    let mut rules = BitGrid::new(255, 255);
 
     for rule in rules_raw.lines() {
-        let (a, b) = rule.split_once('|').unwrap();
-        rules.set(
-            a.parse::<usize>().unwrap() * 255 + b.parse::<usize>().unwrap(),
-            true,
-        );
+        let (a, b) = error::split_once(input, rule, "|")?;
+        rules.insert(ivec2(parse_page(input, a)?, parse_page(input, b)?));
     }
 
     let mut updates = Vec::new();
@@ -23,17 +37,25 @@ pub fn a(input: &str) -> i32 {
         updates.push(
             update
                 .split(',')
-                .map(|u| u.parse::<i32>().unwrap())
-                .collect::<Vec<_>>(),
+                .map(|u| parse_page(input, u))
+                .collect::<Result<Vec<_>>>()?,
         );
     }
 
+    Ok((rules, updates))
+}
+
+pub fn a(input: &str) -> Result<i32> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a((rules, updates): (BitGrid, Vec<Vec<i32>>)) -> Result<i32> {
     let mut sum_of_middle_page_no = 0;
 
     for update in &updates {
         let mut sorted_update = update.clone();
         sorted_update.sort_unstable_by(|a, b| {
-            if rules.get((*a * 255 + *b) as usize).unwrap() {
+            if rules.get(ivec2(*a, *b)) {
                 Ordering::Less
             } else {
                 Ordering::Greater
@@ -46,45 +68,27 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    sum_of_middle_page_no
+    Ok(sum_of_middle_page_no)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 143);
-    assert_eq!(a(INPUT), 5639);
+    assert_eq!(a(TEST_INPUT).unwrap(), 143);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 5639);
 }
 
-pub fn b(input: &str) -> i32 {
-    let (rules_raw, updates_raw) = input.split_once("\n\n").unwrap();
-
-    let mut rules = BitVec::from_elem(65535, false);
-
-    for rule in rules_raw.lines() {
-        let (a, b) = rule.split_once('|').unwrap();
-        rules.set(
-            a.parse::<usize>().unwrap() * 255 + b.parse::<usize>().unwrap(),
-            true,
-        );
-    }
-
-    let mut updates = Vec::new();
-
-    for update in updates_raw.lines() {
-        updates.push(
-            update
-                .split(',')
-                .map(|u| u.parse::<i32>().unwrap())
-                .collect::<Vec<_>>(),
-        );
-    }
+pub fn b(input: &str) -> Result<i32> {
+    solve_b(parse(input)?)
+}
 
+pub(crate) fn solve_b((rules, updates): (BitGrid, Vec<Vec<i32>>)) -> Result<i32> {
     let mut sum_of_middle_page_no = 0;
 
     for update in &updates {
         let mut sorted_update = update.clone();
         sorted_update.sort_unstable_by(|a, b| {
-            if rules.get((*a * 255 + *b) as usize).unwrap() {
+            if rules.get(ivec2(*a, *b)) {
                 Ordering::Less
             } else {
                 Ordering::Greater
@@ -97,11 +101,12 @@ pub fn b(input: &str) -> i32 {
         }
     }
 
-    sum_of_middle_page_no
+    Ok(sum_of_middle_page_no)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 123);
-    assert_eq!(b(INPUT), 5273);
+    assert_eq!(b(TEST_INPUT).unwrap(), 123);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 5273);
 }
@@ -1,3 +1,6 @@
+use crate::error::{self, Result};
+
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/7.txt");
 pub static TEST_INPUT: &str = include_str!("../input/7_test.txt");
 
@@ -32,21 +35,29 @@ fn is_solvable(answer: i64, remaining_numbers: &[i64]) -> bool {
     false
 }
 
-pub fn a(input: &str) -> i64 {
+pub(crate) fn parse(input: &str) -> Result<Vec<(i64, Vec<i64>)>> {
     let mut equations = Vec::new();
 
     for line in input.lines() {
-        let (answer, numbers) = line.split_once(':').unwrap();
-        let answer = answer.parse::<i64>().unwrap();
+        let (answer, numbers) = error::split_once(input, line, ":")?;
+        let answer = error::parse::<i64>(input, answer)?;
         let numbers = numbers
             .trim()
             .split(' ')
-            .map(|n| n.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|n| error::parse::<i64>(input, n))
+            .collect::<Result<Vec<_>>>()?;
 
         equations.push((answer, numbers));
     }
 
+    Ok(equations)
+}
+
+pub fn a(input: &str) -> Result<i64> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a(equations: Vec<(i64, Vec<i64>)>) -> Result<i64> {
     let mut total_calibration_result = 0;
 
     for (answer, numbers) in equations {
@@ -55,13 +66,14 @@ pub fn a(input: &str) -> i64 {
         };
     }
 
-    total_calibration_result
+    Ok(total_calibration_result)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 3749);
-    assert_eq!(a(INPUT), 5512534574980);
+    assert_eq!(a(TEST_INPUT).unwrap(), 3749);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 5512534574980);
 }
 
 fn digits(n: i64) -> i64 {
@@ -123,21 +135,11 @@ fn is_solvable_b(answer: i64, remaining_numbers: &[i64]) -> bool {
     false
 }
 
-pub fn b(input: &str) -> i64 {
-    let mut equations = Vec::new();
-
-    for line in input.lines() {
-        let (answer, numbers) = line.split_once(':').unwrap();
-        let answer = answer.parse::<i64>().unwrap();
-        let numbers = numbers
-            .trim()
-            .split(' ')
-            .map(|n| n.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-
-        equations.push((answer, numbers));
-    }
+pub fn b(input: &str) -> Result<i64> {
+    solve_b(parse(input)?)
+}
 
+pub(crate) fn solve_b(equations: Vec<(i64, Vec<i64>)>) -> Result<i64> {
     let mut total_calibration_result = 0;
 
     for (answer, numbers) in equations {
@@ -146,11 +148,12 @@ pub fn b(input: &str) -> i64 {
         };
     }
 
-    total_calibration_result
+    Ok(total_calibration_result)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 11387);
-    assert_eq!(b(INPUT), 328790210468594);
+    assert_eq!(b(TEST_INPUT).unwrap(), 11387);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 328790210468594);
 }
@@ -1,33 +1,46 @@
+use crate::error::{AocError, Result};
 use std::collections::VecDeque;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/9.txt");
 pub static TEST_INPUT: &str = include_str!("../input/9_test.txt");
 
-struct Block {
+#[derive(Clone)]
+pub(crate) struct Block {
     index: i16,
     file: u8,
     free: u8,
 }
 
-pub fn a(input: &str) -> i64 {
+pub(crate) fn parse(input: &str) -> Result<Vec<Block>> {
+    let disk_map = input.trim();
+
+    if disk_map.is_empty() {
+        return Err(AocError::new("empty disk map"));
+    }
+
+    if let Some(i) = disk_map.bytes().position(|c| !c.is_ascii_digit()) {
+        return Err(AocError::at(input, &disk_map[i..], "expected a digit"));
+    }
+
     let mut blocks = Vec::with_capacity(input.len() / 2);
 
-    for (index, chunk) in input.trim().as_bytes().chunks(2).enumerate() {
-        if chunk.len() == 2 {
-            blocks.push(Block {
-                index: index as i16,
-                file: chunk[0] - 48,
-                free: chunk[1] - 48,
-            });
-        } else {
-            blocks.push(Block {
-                index: index as i16,
-                file: chunk[0] - 48,
-                free: 0,
-            })
-        }
+    for (index, chunk) in disk_map.as_bytes().chunks(2).enumerate() {
+        blocks.push(Block {
+            index: index as i16,
+            file: chunk[0] - b'0',
+            free: chunk.get(1).map_or(0, |c| c - b'0'),
+        });
     }
 
+    Ok(blocks)
+}
+
+pub fn a(input: &str) -> Result<i64> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a(mut blocks: Vec<Block>) -> Result<i64> {
     let mut checksum = 0;
     let mut block_counter = 0;
     let mut free_index = blocks.len() - 1;
@@ -59,34 +72,24 @@ pub fn a(input: &str) -> i64 {
         }
     }
 
-    checksum
+    Ok(checksum)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 1928);
-    assert_eq!(a(INPUT), 6384282079460);
+    assert_eq!(a(TEST_INPUT).unwrap(), 1928);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 6384282079460);
+}
+
+pub fn b(input: &str) -> Result<i64> {
+    solve_b(parse(input)?)
 }
 
 #[allow(clippy::mut_range_bound)]
-pub fn b(input: &str) -> i64 {
-    let mut blocks = VecDeque::with_capacity(input.len());
-
-    for (index, chunk) in input.trim().as_bytes().chunks(2).enumerate() {
-        if chunk.len() == 2 {
-            blocks.push_back(Block {
-                index: index as i16,
-                file: chunk[0] - 48,
-                free: chunk[1] - 48,
-            });
-        } else {
-            blocks.push_back(Block {
-                index: index as i16,
-                file: chunk[0] - 48,
-                free: 0,
-            })
-        }
-    }
+pub(crate) fn solve_b(parsed: Vec<Block>) -> Result<i64> {
+    let mut blocks = VecDeque::with_capacity(parsed.len() * 2);
+    blocks.extend(parsed);
 
     let mut from_index = blocks.len() - 1;
     let mut first_with_space = [0; 10];
@@ -132,11 +135,12 @@ pub fn b(input: &str) -> i64 {
         block_counter += block.free as i32;
     }
 
-    checksum
+    Ok(checksum)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 2858);
-    assert_eq!(b(INPUT), 6408966547049);
+    assert_eq!(b(TEST_INPUT).unwrap(), 2858);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 6408966547049);
 }
@@ -1,71 +1,17 @@
+use crate::{dir::neighbours4, error::Result, grid::Grid};
 use glam::{ivec2, IVec2};
 use smallvec::{smallvec, SmallVec};
-use std::{fmt::Display, str};
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/10.txt");
 pub static TEST_INPUT: &str = include_str!("../input/10_test.txt");
 
-#[derive(Clone)]
-struct Map {
-    data: Vec<u8>,
-    width: i32,
-    height: i32,
-}
-
-impl Map {
-    fn new(input: &str) -> Map {
-        let data = input.replace('\n', "").into_bytes();
-
-        let mut width: i32 = 0;
-
-        if let Some(line) = input.lines().next() {
-            width = line.len() as i32;
-        }
-
-        let height = data.len() as i32 / width;
-
-        Map {
-            data,
-            width,
-            height,
-        }
-    }
-
-    fn ascii_digits_to_u8(mut self) -> Map {
-        for value in self.data.iter_mut() {
-            *value -= 48;
-        }
-
-        self
-    }
-
-    fn get(&self, pos: IVec2) -> u8 {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return Default::default();
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return Default::default();
-        }
-
-        self.data[index as usize]
-    }
-}
-
-impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for line in self.data.chunks(self.width as usize) {
-            writeln!(f, "{}", str::from_utf8(line).unwrap())?;
-        }
-
-        Ok(())
-    }
+pub(crate) fn parse(input: &str) -> Result<Grid<u8>> {
+    Grid::parse_with(input, 0, |c| c.is_ascii_digit().then(|| c - b'0'))
 }
 
 fn find_paths(
-    map: &Map,
+    map: &Grid<u8>,
     reachable_nines: &mut Vec<SmallVec<[(u8, u8); 4]>>,
     pos: IVec2,
 ) -> SmallVec<[(u8, u8); 4]> {
@@ -85,8 +31,7 @@ fn find_paths(
 
     let mut possible_paths = SmallVec::new();
 
-    for possible_dir in [ivec2(1, 0), ivec2(-1, 0), ivec2(0, 1), ivec2(0, -1)] {
-        let candidate_pos = pos + possible_dir;
+    for candidate_pos in neighbours4(pos) {
         let candidate_val = map.get(candidate_pos);
 
         if candidate_val as i32 - value as i32 == 1 {
@@ -104,8 +49,11 @@ fn find_paths(
     possible_paths
 }
 
-pub fn a(input: &str) -> i32 {
-    let map = Map::new(input).ascii_digits_to_u8();
+pub fn a(input: &str) -> Result<i32> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a(map: Grid<u8>) -> Result<i32> {
     let mut reachable_nines = vec![SmallVec::new(); (map.width * map.height) as usize];
 
     let mut sum_of_reachable = 0;
@@ -122,16 +70,17 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    sum_of_reachable
+    Ok(sum_of_reachable)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 36);
-    assert_eq!(a(INPUT), 688);
+    assert_eq!(a(TEST_INPUT).unwrap(), 36);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 688);
 }
 
-fn find_paths_b(map: &Map, reachable_nines: &mut Vec<i32>, pos: IVec2) -> i32 {
+fn find_paths_b(map: &Grid<u8>, reachable_nines: &mut Vec<i32>, pos: IVec2) -> i32 {
     {
         let cached = reachable_nines
             .get((pos.x + pos.y * map.width) as usize)
@@ -148,8 +97,7 @@ fn find_paths_b(map: &Map, reachable_nines: &mut Vec<i32>, pos: IVec2) -> i32 {
 
     let mut possible_paths = 0;
 
-    for possible_dir in [ivec2(1, 0), ivec2(-1, 0), ivec2(0, 1), ivec2(0, -1)] {
-        let candidate_pos = pos + possible_dir;
+    for candidate_pos in neighbours4(pos) {
         let candidate_val = map.get(candidate_pos);
 
         if candidate_val as i32 - value as i32 == 1 {
@@ -162,8 +110,11 @@ fn find_paths_b(map: &Map, reachable_nines: &mut Vec<i32>, pos: IVec2) -> i32 {
     possible_paths
 }
 
-pub fn b(input: &str) -> i32 {
-    let map = Map::new(input).ascii_digits_to_u8();
+pub fn b(input: &str) -> Result<i32> {
+    solve_b(parse(input)?)
+}
+
+pub(crate) fn solve_b(map: Grid<u8>) -> Result<i32> {
     let mut reachable_nines = vec![0; (map.width * map.height) as usize];
 
     let mut sum_of_reachable = 0;
@@ -179,11 +130,12 @@ pub fn b(input: &str) -> i32 {
         }
     }
 
-    sum_of_reachable
+    Ok(sum_of_reachable)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 81);
-    assert_eq!(b(INPUT), 1459);
+    assert_eq!(b(TEST_INPUT).unwrap(), 81);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 1459);
 }
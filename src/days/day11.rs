@@ -1,6 +1,10 @@
-use crate::AdventHashMap;
+use crate::{
+    error::{self, Result},
+    AdventHashMap,
+};
 use std::mem;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/11.txt");
 pub static TEST_INPUT: &str = include_str!("../input/11_test.txt");
 
@@ -19,16 +23,16 @@ fn count_digits(mut n: i64) -> i64 {
     count
 }
 
-pub fn a(input: &str) -> i64 {
+pub fn a(input: &str, blinks: u32) -> Result<i64> {
     let mut numbers = input
         .trim()
         .split(' ')
-        .map(|n| (n.parse().unwrap(), 1))
-        .collect::<AdventHashMap<i64, i64>>();
+        .map(|n| Ok((error::parse(input, n)?, 1)))
+        .collect::<Result<AdventHashMap<i64, i64>>>()?;
 
     let mut new_numbers = AdventHashMap::default();
 
-    for _round in 0..25 {
+    for _round in 0..blinks {
         new_numbers.clear();
 
         for (number, count) in &numbers {
@@ -58,25 +62,26 @@ pub fn a(input: &str) -> i64 {
         mem::swap(&mut numbers, &mut new_numbers);
     }
 
-    numbers.values().sum()
+    Ok(numbers.values().sum())
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 55312);
-    assert_eq!(a(INPUT), 194482);
+    assert_eq!(a(TEST_INPUT, 25).unwrap(), 55312);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT, 25).unwrap(), 194482);
 }
 
-pub fn b(input: &str) -> i64 {
+pub fn b(input: &str, blinks: u32) -> Result<i64> {
     let mut numbers = input
         .trim()
         .split(' ')
-        .map(|n| (n.parse().unwrap(), 1))
-        .collect::<AdventHashMap<i64, i64>>();
+        .map(|n| Ok((error::parse(input, n)?, 1)))
+        .collect::<Result<AdventHashMap<i64, i64>>>()?;
 
     let mut new_numbers = AdventHashMap::default();
 
-    for _round in 0..75 {
+    for _round in 0..blinks {
         new_numbers.clear();
 
         for (number, count) in &numbers {
@@ -106,11 +111,12 @@ pub fn b(input: &str) -> i64 {
         mem::swap(&mut numbers, &mut new_numbers);
     }
 
-    numbers.values().sum()
+    Ok(numbers.values().sum())
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 65601038650482);
-    assert_eq!(b(INPUT), 232454623677743);
+    assert_eq!(b(TEST_INPUT, 75).unwrap(), 65601038650482);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT, 75).unwrap(), 232454623677743);
 }
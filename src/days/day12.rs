@@ -1,6 +1,12 @@
+use crate::{
+    dir::{neighbours4, Dir4},
+    error::Result,
+    grid::Grid,
+};
 use glam::{ivec2, IVec2};
-use std::{collections::HashMap, str};
+use std::collections::HashMap;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/12.txt");
 pub static TEST_INPUT: &str = include_str!("../input/12_test.txt");
 pub static TEST_INPUT_2: &str = include_str!("../input/12_test_2.txt");
@@ -8,132 +14,26 @@ pub static TEST_INPUT_3: &str = include_str!("../input/12_test_3.txt");
 pub static TEST_INPUT_4: &str = include_str!("../input/12_test_4.txt");
 pub static TEST_INPUT_5: &str = include_str!("../input/12_test_5.txt");
 
-#[derive(Clone)]
-struct MapI32 {
-    data: Vec<i32>,
-    width: i32,
-    height: i32,
-}
-
-impl MapI32 {
-    fn empty(width: i32, height: i32) -> MapI32 {
-        MapI32 {
-            data: vec![-1; (width * height) as usize],
-            width,
-            height,
-        }
-    }
-
-    fn get(&self, pos: IVec2) -> i32 {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return -1;
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return -1;
-        }
-
-        self.data[index as usize]
-    }
-
-    fn set(&mut self, pos: IVec2, new: i32) -> bool {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return false;
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return false;
-        }
-
-        self.data[index as usize] = new;
-
-        true
-    }
-}
-
-#[derive(Clone)]
-struct Map {
-    data: Vec<u8>,
-    width: i32,
-    height: i32,
-}
-
-impl Map {
-    fn new(input: &str) -> Map {
-        let data = input.replace('\n', "").into_bytes();
-
-        let mut width: i32 = 0;
-
-        if let Some(line) = input.lines().next() {
-            width = line.len() as i32;
-        }
-
-        let height = data.len() as i32 / width;
-
-        Map {
-            data,
-            width,
-            height,
-        }
-    }
-
-    fn empty(width: i32, height: i32) -> Map {
-        Map {
-            data: vec![b'.'; (width * height) as usize],
-            width,
-            height,
-        }
-    }
-
-    fn get(&self, pos: IVec2) -> u8 {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return b'.';
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return b'.';
-        }
-
-        self.data[index as usize]
-    }
-
-    fn set(&mut self, pos: IVec2, new: u8) -> bool {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return false;
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return false;
-        }
-
-        self.data[index as usize] = new;
-
-        true
-    }
-}
-
-fn flood(processed_positions: &mut Map, map: &Map, region: &mut Vec<IVec2>, p: IVec2, c: u8) {
+fn flood(
+    processed_positions: &mut Grid<u8>,
+    map: &Grid<u8>,
+    region: &mut Vec<IVec2>,
+    p: IVec2,
+    c: u8,
+) {
     if processed_positions.get(p) == b'.' && map.get(p) == c {
         region.push(p);
         processed_positions.set(p, b'x');
 
-        for dir in [ivec2(1, 0), ivec2(-1, 0), ivec2(0, 1), ivec2(0, -1)] {
-            flood(processed_positions, map, region, p + dir, c);
+        for n in neighbours4(p) {
+            flood(processed_positions, map, region, n, c);
         }
     }
 }
 
-pub fn a(input: &str) -> i32 {
-    let map = Map::new(input);
-    let mut processed_positions = Map::empty(map.width, map.height);
+pub fn a(input: &str) -> Result<i32> {
+    let map = Grid::parse(input)?;
+    let mut processed_positions = Grid::new(map.width, map.height, b'.');
     let mut regions = HashMap::<u8, Vec<Vec<IVec2>>>::new();
 
     for y in 0..map.height {
@@ -157,8 +57,8 @@ pub fn a(input: &str) -> i32 {
             let mut border = 0;
 
             for pos in region {
-                for dir in [ivec2(1, 0), ivec2(-1, 0), ivec2(0, 1), ivec2(0, -1)] {
-                    if map.get(pos + dir) != *label {
+                for n in neighbours4(*pos) {
+                    if map.get(n) != *label {
                         border += 1;
                     }
                 }
@@ -168,63 +68,19 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    price as i32
+    Ok(price as i32)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 1930);
-    assert_eq!(a(INPUT), 1431316);
-}
-
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Dir {
-    fn fwd(&self) -> IVec2 {
-        match self {
-            Dir::Up => ivec2(0, -1),
-            Dir::Down => ivec2(0, 1),
-            Dir::Left => ivec2(-1, 0),
-            Dir::Right => ivec2(1, 0),
-        }
-    }
-
-    fn right(&self) -> IVec2 {
-        match self {
-            Dir::Up => ivec2(1, 0),
-            Dir::Down => ivec2(-1, 0),
-            Dir::Left => ivec2(0, -1),
-            Dir::Right => ivec2(0, 1),
-        }
-    }
-
-    fn turn_right(self) -> Dir {
-        match self {
-            Dir::Up => Dir::Right,
-            Dir::Down => Dir::Left,
-            Dir::Left => Dir::Up,
-            Dir::Right => Dir::Down,
-        }
-    }
-
-    fn turn_left(self) -> Dir {
-        match self {
-            Dir::Up => Dir::Left,
-            Dir::Down => Dir::Right,
-            Dir::Left => Dir::Down,
-            Dir::Right => Dir::Up,
-        }
-    }
+    assert_eq!(a(TEST_INPUT).unwrap(), 1930);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 1431316);
 }
 
-pub fn b(input: &str) -> i32 {
-    let map = Map::new(input);
-    let mut processed_positions = Map::empty(map.width, map.height);
+pub fn b(input: &str) -> Result<i32> {
+    let map = Grid::parse(input)?;
+    let mut processed_positions = Grid::new(map.width, map.height, b'.');
     let mut regions = Vec::<(u8, Vec<IVec2>)>::new();
 
     for y in 0..map.height {
@@ -240,7 +96,7 @@ pub fn b(input: &str) -> i32 {
         }
     }
 
-    let mut region_index_map = MapI32::empty(map.width, map.height);
+    let mut region_index_map = Grid::new(map.width, map.height, -1);
     for (index, (_, region)) in regions.iter().enumerate() {
         for p in region {
             region_index_map.set(*p, index as i32);
@@ -250,7 +106,7 @@ pub fn b(input: &str) -> i32 {
     let mut price = 0;
 
     for (label, region) in &regions {
-        let mut region_map = Map::empty(map.width, map.height);
+        let mut region_map = Grid::new(map.width, map.height, b'.');
 
         for pice in region {
             region_map.set(*pice, *label);
@@ -272,7 +128,7 @@ pub fn b(input: &str) -> i32 {
         };
 
         let mut pos = start;
-        let mut dir = Dir::Up;
+        let mut dir = Dir4::Up;
 
         let start_index = region_index_map.get(start);
         let mut contained_in_other = true;
@@ -282,13 +138,13 @@ pub fn b(input: &str) -> i32 {
                 contained_in_other = false;
             }
 
-            if region_map.get(pos + dir.fwd()) == *label {
+            if region_map.get(pos + dir.offset()) == *label {
                 dir = dir.turn_left();
                 border += 1;
-            } else if region_map.get(pos + dir.fwd() + dir.right()) == *label {
-                pos += dir.fwd();
+            } else if region_map.get(pos + dir.offset() + dir.turn_right().offset()) == *label {
+                pos += dir.offset();
             } else {
-                pos += dir.fwd() + dir.right();
+                pos += dir.offset() + dir.turn_right().offset();
                 dir = dir.turn_right();
                 border += 1;
             }
@@ -305,15 +161,16 @@ pub fn b(input: &str) -> i32 {
         price += area * border;
     }
 
-    price as i32
+    Ok(price as i32)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 1206);
-    assert_eq!(b(TEST_INPUT_2), 80);
-    assert_eq!(b(TEST_INPUT_3), 236);
-    assert_eq!(b(TEST_INPUT_4), 368);
-    assert_eq!(b(TEST_INPUT_5), 436);
-    assert_eq!(b(INPUT), 821428);
+    assert_eq!(b(TEST_INPUT).unwrap(), 1206);
+    assert_eq!(b(TEST_INPUT_2).unwrap(), 80);
+    assert_eq!(b(TEST_INPUT_3).unwrap(), 236);
+    assert_eq!(b(TEST_INPUT_4).unwrap(), 368);
+    assert_eq!(b(TEST_INPUT_5).unwrap(), 436);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 821428);
 }
@@ -1,45 +1,48 @@
+use crate::{
+    error::{self, Result},
+    parse,
+};
 use glam::IVec2;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/13.txt");
 pub static TEST_INPUT: &str = include_str!("../input/13_test.txt");
 
-#[derive(Default)]
-struct Machine {
+#[derive(Clone)]
+pub(crate) struct Machine {
     a: IVec2,
     b: IVec2,
     p: IVec2,
 }
 
-pub fn a(input: &str) -> i32 {
-    let mut minimum_tokens = 0;
-
-    for machine_str in input.trim().split("\n\n") {
-        let mut machine = Machine::default();
-
-        let (line1, rest) = machine_str.split_once('\n').unwrap();
-        let (line2, line3) = rest.split_once('\n').unwrap();
-
-        {
-            let (left, right) = line1.split_once(',').unwrap();
-
-            machine.a.x = left.trim_start_matches("Button A: X+").parse().unwrap();
-            machine.a.y = right.trim_start_matches(" Y+").parse().unwrap();
-        }
-
-        {
-            let (left, right) = line2.split_once(',').unwrap();
+pub(crate) fn parse(input: &str) -> Result<Vec<Machine>> {
+    let mut machines = Vec::new();
+
+    for machine_str in parse::sections(input) {
+        let mut lines = machine_str.lines();
+        let mut next_line = |pattern| {
+            let line = lines.next().unwrap_or(error::end(machine_str));
+            parse::record(input, line, pattern).map(IVec2::from_array)
+        };
+
+        machines.push(Machine {
+            a: next_line("Button A: X+{}, Y+{}")?,
+            b: next_line("Button B: X+{}, Y+{}")?,
+            p: next_line("Prize: X={}, Y={}")?,
+        });
+    }
 
-            machine.b.x = left.trim_start_matches("Button B: X+").parse().unwrap();
-            machine.b.y = right.trim_start_matches(" Y+").parse().unwrap();
-        }
+    Ok(machines)
+}
 
-        {
-            let (left, right) = line3.split_once(',').unwrap();
+pub fn a(input: &str) -> Result<i32> {
+    solve_a(parse(input)?)
+}
 
-            machine.p.x = left.trim_start_matches("Prize: X=").parse().unwrap();
-            machine.p.y = right.trim_start_matches(" Y=").parse().unwrap();
-        }
+pub(crate) fn solve_a(machines: Vec<Machine>) -> Result<i32> {
+    let mut minimum_tokens = 0;
 
+    for machine in machines {
         let x = (machine.a.x, machine.b.x, machine.p.x);
         let y = (machine.a.y, machine.b.y, machine.p.y);
 
@@ -61,45 +64,24 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    minimum_tokens
+    Ok(minimum_tokens)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 480);
-    assert_eq!(a(INPUT), 30413);
+    assert_eq!(a(TEST_INPUT).unwrap(), 480);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 30413);
 }
 
-pub fn b(input: &str) -> i64 {
-    let mut minimum_tokens = 0;
-
-    for machine_str in input.trim().split("\n\n") {
-        let mut machine = Machine::default();
-
-        let (line1, rest) = machine_str.split_once('\n').unwrap();
-        let (line2, line3) = rest.split_once('\n').unwrap();
-
-        {
-            let (left, right) = line1.split_once(',').unwrap();
-
-            machine.a.x = left.trim_start_matches("Button A: X+").parse().unwrap();
-            machine.a.y = right.trim_start_matches(" Y+").parse().unwrap();
-        }
-
-        {
-            let (left, right) = line2.split_once(',').unwrap();
-
-            machine.b.x = left.trim_start_matches("Button B: X+").parse().unwrap();
-            machine.b.y = right.trim_start_matches(" Y+").parse().unwrap();
-        }
-
-        {
-            let (left, right) = line3.split_once(',').unwrap();
+pub fn b(input: &str) -> Result<i64> {
+    solve_b(parse(input)?)
+}
 
-            machine.p.x = left.trim_start_matches("Prize: X=").parse().unwrap();
-            machine.p.y = right.trim_start_matches(" Y=").parse().unwrap();
-        }
+pub(crate) fn solve_b(machines: Vec<Machine>) -> Result<i64> {
+    let mut minimum_tokens = 0;
 
+    for machine in machines {
         let x = (
             machine.a.x as i64,
             machine.b.x as i64,
@@ -129,11 +111,22 @@ pub fn b(input: &str) -> i64 {
         }
     }
 
-    minimum_tokens
+    Ok(minimum_tokens)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 875318608908);
-    assert_eq!(b(INPUT), 92827349540204);
+    assert_eq!(b(TEST_INPUT).unwrap(), 875318608908);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 92827349540204);
+}
+
+#[test]
+fn test_parse_error() {
+    let input = TEST_INPUT.replace("Prize: X=12748", "Price: X=12748");
+
+    assert_eq!(
+        a(&input).unwrap_err().to_string(),
+        "line 7, column 1: expected 'Prize: X='"
+    );
 }
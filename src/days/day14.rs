@@ -1,6 +1,12 @@
+use crate::{
+    cycle,
+    error::{AocError, Result},
+    parse,
+};
 use glam::IVec2;
 use std::str;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/14.txt");
 pub static TEST_INPUT: &str = include_str!("../input/14_test.txt");
 
@@ -38,33 +44,75 @@ impl Map {
     }
 }
 
-#[derive(Default, Debug)]
-struct Robots {
-    pos_x: Vec<i8>,
-    pos_y: Vec<i8>,
-    speed_x: Vec<i8>,
-    speed_y: Vec<i8>,
+#[derive(Clone, Default, Debug)]
+pub struct Robots {
+    pub pos_x: Vec<i8>,
+    pub pos_y: Vec<i8>,
+    pub speed_x: Vec<i8>,
+    pub speed_y: Vec<i8>,
 }
 
-pub fn a(input: &str, size: IVec2) -> i32 {
-    let mut robots = Robots::default();
+impl Robots {
+    pub fn parse(input: &str) -> Result<Robots> {
+        let mut robots = Robots::default();
 
-    for line in input.lines() {
-        let (left, right) = line.split_once(' ').unwrap();
+        for line in input.lines() {
+            let [x, y, dx, dy] = parse::record(input, line, "p={},{} v={},{}")?;
 
-        let (x, y) = left.split_once(',').unwrap();
-        let x = x.trim_start_matches("p=").parse().unwrap();
-        let y = y.parse().unwrap();
+            robots.pos_x.push(x);
+            robots.pos_y.push(y);
+            robots.speed_x.push(dx);
+            robots.speed_y.push(dy);
+        }
+
+        Ok(robots)
+    }
+
+    /// Errors if a robot starts outside a room of `size`, like when the room is set smaller than
+    /// the one the input was made for.
+    pub fn check_room(&self, size: IVec2) -> Result<()> {
+        for i in 0..self.pos_x.len() {
+            let (x, y) = (self.pos_x[i] as i32, self.pos_y[i] as i32);
+
+            if !(0..size.x).contains(&x) || !(0..size.y).contains(&y) {
+                return Err(AocError::new(format!(
+                    "the robot on line {} at {x},{y} is outside the {}x{} room",
+                    i + 1,
+                    size.x,
+                    size.y
+                )));
+            }
+        }
 
-        let (dx, dy) = right.split_once(',').unwrap();
-        let dx = dx.trim_start_matches("v=").parse().unwrap();
-        let dy = dy.parse().unwrap();
+        Ok(())
+    }
 
-        robots.pos_x.push(x);
-        robots.pos_y.push(y);
-        robots.speed_x.push(dx);
-        robots.speed_y.push(dy);
+    /// The number of steps after which all robots are back where they started.
+    pub(crate) fn period(&self, size: IVec2) -> usize {
+        let axis_period = |pos: &[i8], speed: &[i8], size: i32| {
+            let step = |pos: &Vec<i8>| {
+                pos.iter()
+                    .zip(speed)
+                    .map(|(p, v)| (*p as i16 + *v as i16).rem_euclid(size as _) as i8)
+                    .collect::<Vec<_>>()
+            };
+
+            cycle::brent(pos.to_vec(), step).length
+        };
+
+        cycle::lcm(
+            axis_period(&self.pos_x, &self.speed_x, size.x),
+            axis_period(&self.pos_y, &self.speed_y, size.y),
+        )
     }
+}
+
+pub fn a(input: &str, size: IVec2) -> Result<i32> {
+    solve_a(Robots::parse(input)?, size)
+}
+
+pub(crate) fn solve_a(mut robots: Robots, size: IVec2) -> Result<i32> {
+    robots.check_room(size)?;
 
     for _step in 0..100 {
         for i in 0..robots.pos_x.len() {
@@ -98,44 +146,31 @@ pub fn a(input: &str, size: IVec2) -> i32 {
         }
     }
 
-    q1 * q2 * q3 * q4
+    Ok(q1 * q2 * q3 * q4)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT, glam::ivec2(11, 7)), 12);
-    assert_eq!(a(INPUT, glam::ivec2(101, 103)), 221655456);
+    assert_eq!(a(TEST_INPUT, glam::ivec2(11, 7)).unwrap(), 12);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT, glam::ivec2(101, 103)).unwrap(), 221655456);
 }
 
-pub fn b(input: &str, size: IVec2) -> i32 {
-    let mut robots = Robots::default();
-
-    let mut map = Map::empty(size.x, size.y);
-
-    for line in input.lines() {
-        let (left, right) = line.split_once(' ').unwrap();
-
-        let (x, y) = left.split_once(',').unwrap();
-        let x = x.trim_start_matches("p=").parse().unwrap();
-        let y = y.parse().unwrap();
+pub fn b(input: &str, size: IVec2) -> Result<i32> {
+    solve_b(Robots::parse(input)?, size)
+}
 
-        let (dx, dy) = right.split_once(',').unwrap();
-        let dx = dx.trim_start_matches("v=").parse().unwrap();
-        let dy = dy.parse().unwrap();
+pub(crate) fn solve_b(mut robots: Robots, size: IVec2) -> Result<i32> {
+    robots.check_room(size)?;
 
-        robots.pos_x.push(x);
-        robots.pos_y.push(y);
-        robots.speed_x.push(dx);
-        robots.speed_y.push(dy);
+    let mut map = Map::empty(size.x, size.y);
 
-        map.modify(x, y, 1);
+    for i in 0..robots.pos_x.len() {
+        map.modify(robots.pos_x[i], robots.pos_y[i], 1);
     }
 
-    let mut step = 0;
-
-    loop {
-        step += 1;
-
+    // Every arrangement is repeated after the period, so checking one period is enough.
+    for step in 1..=robots.period(size) {
         let mut conflict = false;
 
         map.data.fill(0);
@@ -157,14 +192,24 @@ pub fn b(input: &str, size: IVec2) -> i32 {
         }
 
         if !conflict {
-            break;
+            return Ok(step as i32);
         }
     }
 
-    step
+    Err(AocError::new("the robots always overlap"))
+}
+
+#[test]
+fn test_check_room() {
+    assert_eq!(
+        a(TEST_INPUT, glam::ivec2(7, 7)).unwrap_err().to_string(),
+        "the robot on line 3 at 10,3 is outside the 7x7 room"
+    );
+    assert!(b(TEST_INPUT, glam::ivec2(11, 4)).is_err());
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(INPUT, glam::ivec2(101, 103)), 7858);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT, glam::ivec2(101, 103)).unwrap(), 7858);
 }
@@ -1,98 +1,198 @@
-use std::arch::x86_64::{
-    _mm256_loadu_si256, _mm256_storeu_epi8, _mm512_add_epi16, _mm512_cmpge_epi16_mask,
-    _mm512_cmplt_epi16_mask, _mm512_cvtepi16_epi8, _mm512_cvtepi8_epi16, _mm512_mask_add_epi16,
-    _mm512_mask_sub_epi16, _mm512_set1_epi16,
+use crate::{
+    day14::Robots,
+    error::{AocError, Result},
 };
-
-#[derive(Clone)]
-struct Map {
-    data: Vec<i8>,
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The implementations of part b, from widest to narrowest.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kernel {
+    /// 32 robots at a time, only built with the `nightly` feature.
+    Avx512,
+    /// 16 robots at a time.
+    Avx2,
+    /// 8 robots at a time.
+    Sse41,
+    /// [`crate::day14::b`].
+    Scalar,
 }
 
-impl Map {
-    fn empty(width: i32, height: i32) -> Map {
-        Map {
-            data: vec![0; (width * height) as usize],
+impl Kernel {
+    pub const ALL: [Kernel; 4] = [Kernel::Avx512, Kernel::Avx2, Kernel::Sse41, Kernel::Scalar];
+
+    pub fn from_arg(arg: &str) -> std::result::Result<Kernel, String> {
+        match arg {
+            "avx512" => Ok(Kernel::Avx512),
+            "avx2" => Ok(Kernel::Avx2),
+            "sse4.1" => Ok(Kernel::Sse41),
+            "scalar" => Ok(Kernel::Scalar),
+            _ => Err(format!(
+                "Unknown kernel '{arg}', expected avx512, avx2, sse4.1 or scalar"
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kernel::Avx512 => "avx512",
+            Kernel::Avx2 => "avx2",
+            Kernel::Sse41 => "sse4.1",
+            Kernel::Scalar => "scalar",
+        }
+    }
+
+    /// Whether the kernel is built and the CPU has the instructions it needs.
+    pub fn is_supported(self) -> bool {
+        match self {
+            #[cfg(all(target_arch = "x86_64", feature = "nightly"))]
+            Kernel::Avx512 => {
+                is_x86_feature_detected!("avx512f")
+                    && is_x86_feature_detected!("avx512bw")
+                    && is_x86_feature_detected!("avx512vl")
+                    && is_x86_feature_detected!("avx2")
+            }
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Sse41 => is_x86_feature_detected!("sse4.1"),
+            Kernel::Scalar => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
         }
     }
+
+    /// The widest supported kernel.
+    pub fn detect() -> Kernel {
+        Kernel::ALL
+            .into_iter()
+            .find(|k| k.is_supported())
+            .unwrap_or(Kernel::Scalar)
+    }
 }
 
-#[derive(Default, Debug)]
-struct Robots {
-    pos_x: Vec<i8>,
-    pos_y: Vec<i8>,
-    speed_x: Vec<i8>,
-    speed_y: Vec<i8>,
+/// The kernel set with [`force`], `u8::MAX` when it is detected.
+static FORCED: AtomicU8 = AtomicU8::new(u8::MAX);
+
+/// Makes [`b`] use `kernel` instead of the detected one, `None` goes back to detecting.
+pub fn force(kernel: Option<Kernel>) -> std::result::Result<(), String> {
+    match kernel {
+        Some(kernel) if !kernel.is_supported() => Err(format!(
+            "The {} kernel isn't supported on this machine or build",
+            kernel.name()
+        )),
+        Some(kernel) => {
+            FORCED.store(kernel as u8, Ordering::Relaxed);
+            Ok(())
+        }
+        None => {
+            FORCED.store(u8::MAX, Ordering::Relaxed);
+            Ok(())
+        }
+    }
 }
 
-pub fn b(input: &str, size: glam::IVec2) -> i32 {
-    if cfg!(target_arch = "x86_64")
-        && is_x86_feature_detected!("avx512f")
-        && is_x86_feature_detected!("avx512bw")
-        && is_x86_feature_detected!("avx512vl")
-        && is_x86_feature_detected!("avx2")
-    {
-        unsafe { b_avx_512(input, (size.x, size.y)) }
-    } else {
-        crate::day14::b(input, size)
+/// The kernel [`b`] uses.
+pub fn kernel() -> Kernel {
+    match FORCED.load(Ordering::Relaxed) {
+        u8::MAX => Kernel::detect(),
+        i => Kernel::ALL[i as usize],
     }
 }
 
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "avx512f,avx512bw,avx512vl,avx2")]
-#[allow(clippy::missing_safety_doc)]
-unsafe fn b_avx_512(input: &str, size: (i32, i32)) -> i32 {
-    use std::arch::x86_64::{_mm512_mullo_epi16, _mm512_storeu_epi16};
+pub fn b(input: &str, size: glam::IVec2) -> Result<i32> {
+    b_with(kernel(), input, size)
+}
 
-    let mut robots = Robots::default();
+/// Part b with a given kernel, falls back to the scalar one if it isn't supported.
+pub fn b_with(kernel: Kernel, input: &str, size: glam::IVec2) -> Result<i32> {
+    solve_b_with(kernel, Robots::parse(input)?, size)
+}
 
-    let mut map = Map::empty(size.0, size.1);
+pub(crate) fn solve_b(robots: Robots, size: glam::IVec2) -> Result<i32> {
+    solve_b_with(kernel(), robots, size)
+}
 
-    let mut count = 0;
+fn solve_b_with(kernel: Kernel, robots: Robots, size: glam::IVec2) -> Result<i32> {
+    robots.check_room(size)?;
 
-    for line in input.lines() {
-        let (left, right) = line.split_once(' ').unwrap();
+    if !kernel.is_supported() {
+        return crate::day14::solve_b(robots, size);
+    }
 
-        let (x, y) = left.split_once(',').unwrap();
-        let x = x.trim_start_matches("p=").parse().unwrap();
-        let y = y.parse().unwrap();
+    match kernel {
+        #[cfg(all(target_arch = "x86_64", feature = "nightly"))]
+        Kernel::Avx512 => unsafe { b_avx_512(robots, (size.x, size.y)) },
+        #[cfg(target_arch = "x86_64")]
+        Kernel::Avx2 => unsafe { b_avx_2(robots, (size.x, size.y)) },
+        #[cfg(target_arch = "x86_64")]
+        Kernel::Sse41 => unsafe { b_sse_41(robots, (size.x, size.y)) },
+        _ => crate::day14::solve_b(robots, size),
+    }
+}
 
-        let (dx, dy) = right.split_once(',').unwrap();
-        let dx = dx.trim_start_matches("v=").parse().unwrap();
-        let dy = dy.parse().unwrap();
+/// The robots padded with ones standing still at 0,0 to a multiple of `lanes`, the number of
+/// real robots and their period.
+fn padded(mut robots: Robots, size: (i32, i32), lanes: usize) -> (Robots, usize, usize) {
+    // The kernels wrap once per step, which only works for positions and speeds in `0..size`.
+    let wrap = |values: &mut Vec<i8>, size: i32| {
+        for v in values {
+            *v = (*v as i32).rem_euclid(size) as i8;
+        }
+    };
 
-        robots.pos_x.push(x);
-        robots.pos_y.push(y);
-        robots.speed_x.push(dx);
-        robots.speed_y.push(dy);
+    wrap(&mut robots.pos_x, size.0);
+    wrap(&mut robots.pos_y, size.1);
+    wrap(&mut robots.speed_x, size.0);
+    wrap(&mut robots.speed_y, size.1);
 
-        count += 1;
-    }
+    let count = robots.pos_x.len();
+    let period = robots.period(glam::ivec2(size.0, size.1));
 
-    while robots.pos_x.len() < 512 {
+    while !robots.pos_x.len().is_multiple_of(lanes) || robots.pos_x.is_empty() {
         robots.pos_x.push(0);
         robots.pos_y.push(0);
         robots.speed_x.push(0);
         robots.speed_y.push(0);
     }
 
-    let mut step = 0;
+    (robots, count, period)
+}
+
+/// Marks the cells at `index` for the robots from `first` on, returns true if any of them was
+/// marked already. The padding robots after `count` are skipped.
+fn place(map: &mut [i8], index: &[i16], first: usize, count: usize) -> bool {
+    let mut conflict = false;
+
+    for &index in index.iter().take(count.saturating_sub(first)) {
+        let cell = &mut map[index as usize];
+        conflict |= *cell > 0;
+        *cell = 1;
+    }
+
+    conflict
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "nightly"))]
+#[target_feature(enable = "avx512f,avx512bw,avx512vl,avx2")]
+unsafe fn b_avx_512(robots: Robots, size: (i32, i32)) -> Result<i32> {
+    const LANES: usize = 32;
+
+    let (mut robots, count, period) = padded(robots, size, LANES);
+    let mut map = vec![0i8; (size.0 * size.1) as usize];
 
     let zero = _mm512_set1_epi16(0);
     let width = _mm512_set1_epi16(size.0 as _);
     let height = _mm512_set1_epi16(size.1 as _);
 
-    loop {
-        step += 1;
-
+    for step in 1..=period {
         let mut conflict = false;
-        map.data.fill(0);
-
-        const LANES: usize = 32;
+        map.fill(0);
 
         for i in (0..robots.pos_x.len()).step_by(LANES) {
-            let x_addr = robots.pos_x.as_ptr().add(i);
-            let y_addr = robots.pos_y.as_ptr().add(i);
+            let x_addr = robots.pos_x.as_mut_ptr().add(i);
+            let y_addr = robots.pos_y.as_mut_ptr().add(i);
             let dx_addr = robots.speed_x.as_ptr().add(i);
             let dy_addr = robots.speed_y.as_ptr().add(i);
 
@@ -120,37 +220,202 @@ unsafe fn b_avx_512(input: &str, size: (i32, i32)) -> i32 {
             _mm256_storeu_epi8(x_addr as _, _mm512_cvtepi16_epi8(new_x));
             _mm256_storeu_epi8(y_addr as _, _mm512_cvtepi16_epi8(new_y));
 
-            let mut index = [0i16; LANES];
-            let index_y = _mm512_mullo_epi16(new_y, width);
-            let index_x = _mm512_add_epi16(index_y, new_x);
-            _mm512_storeu_epi16(index.as_mut_ptr(), index_x);
+            if !conflict {
+                let mut index = [0i16; LANES];
+                let index_y = _mm512_mullo_epi16(new_y, width);
+                _mm512_storeu_epi16(index.as_mut_ptr(), _mm512_add_epi16(index_y, new_x));
+
+                conflict = place(&mut map, &index, i, count);
+            }
+        }
+
+        if !conflict {
+            return Ok(step as i32);
+        }
+    }
+
+    Err(AocError::new("the robots always overlap"))
+}
+
+/// Wraps each lane of `v` into `0..size`, for values in `-size..2 * size`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn wrap_avx_2(v: __m256i, size: __m256i) -> __m256i {
+    let over = _mm256_cmpgt_epi16(v, _mm256_sub_epi16(size, _mm256_set1_epi16(1)));
+    let v = _mm256_sub_epi16(v, _mm256_and_si256(over, size));
+    let under = _mm256_cmpgt_epi16(_mm256_setzero_si256(), v);
+    _mm256_add_epi16(v, _mm256_and_si256(under, size))
+}
+
+/// Stores the 16 lanes of `v` as bytes, they have to fit in an `i8`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn store_avx_2(addr: *mut i8, v: __m256i) {
+    // Packing works within each 128 bit half, so the halves are put back together after.
+    let packed = _mm256_permute4x64_epi64(_mm256_packs_epi16(v, v), 0b1000);
+    _mm_storeu_si128(addr as _, _mm256_castsi256_si128(packed));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn b_avx_2(robots: Robots, size: (i32, i32)) -> Result<i32> {
+    const LANES: usize = 16;
+
+    let (mut robots, count, period) = padded(robots, size, LANES);
+    let mut map = vec![0i8; (size.0 * size.1) as usize];
+
+    let width = _mm256_set1_epi16(size.0 as _);
+    let height = _mm256_set1_epi16(size.1 as _);
+
+    for step in 1..=period {
+        let mut conflict = false;
+        map.fill(0);
+
+        for i in (0..robots.pos_x.len()).step_by(LANES) {
+            let x_addr = robots.pos_x.as_mut_ptr().add(i);
+            let y_addr = robots.pos_y.as_mut_ptr().add(i);
+            let dx_addr = robots.speed_x.as_ptr().add(i);
+            let dy_addr = robots.speed_y.as_ptr().add(i);
+
+            let x = _mm256_cvtepi8_epi16(_mm_loadu_si128(x_addr as _));
+            let y = _mm256_cvtepi8_epi16(_mm_loadu_si128(y_addr as _));
+
+            let dx = _mm256_cvtepi8_epi16(_mm_loadu_si128(dx_addr as _));
+            let dy = _mm256_cvtepi8_epi16(_mm_loadu_si128(dy_addr as _));
+
+            let new_x = wrap_avx_2(_mm256_add_epi16(x, dx), width);
+            let new_y = wrap_avx_2(_mm256_add_epi16(y, dy), height);
+
+            store_avx_2(x_addr, new_x);
+            store_avx_2(y_addr, new_y);
+
+            if !conflict {
+                let mut index = [0i16; LANES];
+                let index_y = _mm256_mullo_epi16(new_y, width);
+                _mm256_storeu_si256(index.as_mut_ptr() as _, _mm256_add_epi16(index_y, new_x));
+
+                conflict = place(&mut map, &index, i, count);
+            }
+        }
+
+        if !conflict {
+            return Ok(step as i32);
+        }
+    }
+
+    Err(AocError::new("the robots always overlap"))
+}
+
+/// Wraps each lane of `v` into `0..size`, for values in `-size..2 * size`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn wrap_sse_41(v: __m128i, size: __m128i) -> __m128i {
+    let over = _mm_cmpgt_epi16(v, _mm_sub_epi16(size, _mm_set1_epi16(1)));
+    let v = _mm_sub_epi16(v, _mm_and_si128(over, size));
+    let under = _mm_cmplt_epi16(v, _mm_setzero_si128());
+    _mm_add_epi16(v, _mm_and_si128(under, size))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn b_sse_41(robots: Robots, size: (i32, i32)) -> Result<i32> {
+    const LANES: usize = 8;
+
+    let (mut robots, count, period) = padded(robots, size, LANES);
+    let mut map = vec![0i8; (size.0 * size.1) as usize];
+
+    let width = _mm_set1_epi16(size.0 as _);
+    let height = _mm_set1_epi16(size.1 as _);
+
+    for step in 1..=period {
+        let mut conflict = false;
+        map.fill(0);
+
+        for i in (0..robots.pos_x.len()).step_by(LANES) {
+            let x_addr = robots.pos_x.as_mut_ptr().add(i);
+            let y_addr = robots.pos_y.as_mut_ptr().add(i);
+            let dx_addr = robots.speed_x.as_ptr().add(i);
+            let dy_addr = robots.speed_y.as_ptr().add(i);
+
+            let x = _mm_cvtepi8_epi16(_mm_loadl_epi64(x_addr as _));
+            let y = _mm_cvtepi8_epi16(_mm_loadl_epi64(y_addr as _));
+
+            let dx = _mm_cvtepi8_epi16(_mm_loadl_epi64(dx_addr as _));
+            let dy = _mm_cvtepi8_epi16(_mm_loadl_epi64(dy_addr as _));
+
+            let new_x = wrap_sse_41(_mm_add_epi16(x, dx), width);
+            let new_y = wrap_sse_41(_mm_add_epi16(y, dy), height);
+
+            _mm_storel_epi64(x_addr as _, _mm_packs_epi16(new_x, new_x));
+            _mm_storel_epi64(y_addr as _, _mm_packs_epi16(new_y, new_y));
 
             if !conflict {
-                for ii in 0..LANES {
-                    let i = i + ii;
-                    if i >= count {
-                        break;
-                    }
-                    let index = *index.get_unchecked(ii);
-
-                    let robot_in_pos = *map.data.get_unchecked(index as usize);
-                    if robot_in_pos > 0 {
-                        conflict = true;
-                    }
-                    *map.data.get_unchecked_mut(index as usize) = 1;
-                }
+                let mut index = [0i16; LANES];
+                let index_y = _mm_mullo_epi16(new_y, width);
+                _mm_storeu_si128(index.as_mut_ptr() as _, _mm_add_epi16(index_y, new_x));
+
+                conflict = place(&mut map, &index, i, count);
             }
         }
 
         if !conflict {
-            break;
+            return Ok(step as i32);
         }
     }
 
-    step
+    Err(AocError::new("the robots always overlap"))
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(crate::day14::INPUT, glam::ivec2(101, 103)), 7858);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(crate::day14::INPUT, glam::ivec2(101, 103)).unwrap(), 7858);
+}
+
+#[test]
+fn test_kernels() {
+    let mut rng = fastrand::Rng::with_seed(14);
+
+    let mut inputs = vec![(crate::day14::TEST_INPUT.to_string(), glam::ivec2(11, 7))];
+
+    #[cfg(feature = "embedded-inputs")]
+    {
+        inputs.push((crate::day14::INPUT.to_string(), glam::ivec2(101, 103)));
+        // Most positions and speeds of the real input are outside a small room.
+        inputs.push((crate::day14::INPUT.to_string(), glam::ivec2(11, 7)));
+    }
+
+    // Few robots on a large map, so most steps have no overlap, and counts that aren't a
+    // multiple of any lane count. Speeds go up to the limits of an `i8`, past the room size.
+    for (robots, size) in [1, 7, 33, 100].into_iter().flat_map(|robots| {
+        [
+            (robots, glam::ivec2(101, 103)),
+            (robots, glam::ivec2(11, 7)),
+        ]
+    }) {
+        let input = (0..robots)
+            .map(|_| {
+                format!(
+                    "p={},{} v={},{}\n",
+                    rng.i32(0..size.x),
+                    rng.i32(0..size.y),
+                    rng.i32(-128..128),
+                    rng.i32(-128..128)
+                )
+            })
+            .collect::<String>();
+        inputs.push((input, size));
+    }
+
+    for (input, size) in &inputs {
+        let expected = crate::day14::b(input, *size).map_err(|e| e.to_string());
+
+        for kernel in Kernel::ALL.into_iter().filter(|k| k.is_supported()) {
+            assert_eq!(
+                b_with(kernel, input, *size).map_err(|e| e.to_string()),
+                expected,
+                "{kernel:?} {size}"
+            );
+        }
+    }
 }
@@ -1,102 +1,20 @@
-use std::{fmt::Display, str};
+use crate::{
+    dir::Dir4,
+    error::{AocError, Result},
+    grid::Grid,
+    parse,
+    render::{Frame, Rgb, Sink},
+};
 
 use glam::{ivec2, IVec2};
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/15.txt");
 pub static TEST_INPUT: &str = include_str!("../input/15_test.txt");
 pub static TEST_INPUT_2: &str = include_str!("../input/15_test_2.txt");
 pub static TEST_INPUT_3: &str = include_str!("../input/15_test_3.txt");
 
-struct Map {
-    data: Vec<u8>,
-    width: i32,
-    height: i32,
-}
-
-impl Map {
-    fn new(input: &str) -> Map {
-        let data = input.replace('\n', "").into_bytes();
-
-        let mut width: i32 = 0;
-
-        if let Some(line) = input.lines().next() {
-            width = line.len() as i32;
-        }
-
-        let height = data.len() as i32 / width;
-
-        Map {
-            data,
-            width,
-            height,
-        }
-    }
-
-    fn empty(width: i32, height: i32) -> Map {
-        Map {
-            data: vec![b'.'; (width * height) as usize],
-            width,
-            height,
-        }
-    }
-
-    fn get(&self, pos: IVec2) -> u8 {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return b'.';
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return b'.';
-        }
-
-        self.data[index as usize]
-    }
-
-    fn set(&mut self, pos: IVec2, new: u8) -> bool {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return false;
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return false;
-        }
-
-        self.data[index as usize] = new;
-
-        true
-    }
-
-    fn find_first(&self, needle: u8) -> Option<IVec2> {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let pos = ivec2(x, y);
-                let v = self.get(pos);
-
-                if v == needle {
-                    return Some(pos);
-                }
-            }
-        }
-
-        None
-    }
-}
-
-impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for line in self.data.chunks(self.width as usize) {
-            writeln!(f, "{}", str::from_utf8(line).unwrap())?;
-        }
-
-        Ok(())
-    }
-}
-
-fn resolve_collision(map: &mut Map, pos: IVec2, dir: IVec2, payload: bool) -> bool {
+fn resolve_collision(map: &mut Grid<u8>, pos: IVec2, dir: IVec2, payload: bool) -> bool {
     let v = map.get(pos);
 
     match v {
@@ -124,20 +42,17 @@ fn resolve_collision(map: &mut Map, pos: IVec2, dir: IVec2, payload: bool) -> bo
     }
 }
 
-pub fn a(input: &str) -> i32 {
-    let (map, instructions) = input.split_once("\n\n").unwrap();
+pub fn a(input: &str) -> Result<i32> {
+    let (mut map, instructions) = parse::grid_and_instructions(input, "#.O@", "<>^v")?;
 
-    let mut map = Map::new(map);
-    let mut pos = map.find_first(b'@').unwrap();
+    let mut pos = map
+        .find_first(b'@')
+        .ok_or_else(|| AocError::new("no robot '@' in map"))?;
     map.set(pos, b'.');
 
-    for instruction in instructions.as_bytes() {
-        let dir = match *instruction {
-            b'<' => ivec2(-1, 0),
-            b'>' => ivec2(1, 0),
-            b'^' => ivec2(0, -1),
-            b'v' => ivec2(0, 1),
-            _ => continue,
+    for instruction in &instructions {
+        let Some(dir) = Dir4::from_arrow(*instruction).map(Dir4::offset) else {
+            continue;
         };
 
         let new_pos = pos + dir;
@@ -158,19 +73,20 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    score
+    Ok(score)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT_3), 908);
-    assert_eq!(a(TEST_INPUT_2), 2028);
-    assert_eq!(a(TEST_INPUT), 10092);
-    assert_eq!(a(INPUT), 1509074);
+    assert_eq!(a(TEST_INPUT_3).unwrap(), 908);
+    assert_eq!(a(TEST_INPUT_2).unwrap(), 2028);
+    assert_eq!(a(TEST_INPUT).unwrap(), 10092);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 1509074);
 }
 
 fn resolve_collision_b(
-    map: &mut Map,
+    map: &mut Grid<u8>,
     pos: IVec2,
     dir: IVec2,
     payload: Option<u8>,
@@ -229,7 +145,7 @@ fn resolve_collision_b(
     }
 }
 
-fn resolve_collision_expand(map: &mut Map, pos: IVec2, dir: IVec2, apply: bool) -> bool {
+fn resolve_collision_expand(map: &mut Grid<u8>, pos: IVec2, dir: IVec2, apply: bool) -> bool {
     let v = map.get(pos);
 
     let next_ok = resolve_collision_b(map, pos + dir, dir, Some(v), apply);
@@ -241,16 +157,17 @@ fn resolve_collision_expand(map: &mut Map, pos: IVec2, dir: IVec2, apply: bool)
     next_ok
 }
 
-pub fn b(input: &str) -> i32 {
-    let (map, instructions) = input.split_once("\n\n").unwrap();
-    let original_map = Map::new(map);
-
-    let mut map = Map::empty(original_map.width * 2, original_map.height);
+/// The map with every tile twice as wide, and the robot's position.
+fn wide_map(input: &str) -> Result<(Grid<u8>, IVec2, Vec<u8>)> {
+    let (original_map, instructions) = parse::grid_and_instructions(input, "#.O@", "<>^v")?;
 
-    let mut pos = ivec2(0, 0);
+    let robot = original_map
+        .find_first(b'@')
+        .ok_or_else(|| AocError::new("no robot '@' in map"))?;
+    let mut map = Grid::new(original_map.width * 2, original_map.height, b'.');
 
     for y in 0..original_map.height {
-        for x in 0..original_map.height {
+        for x in 0..original_map.width {
             let v = original_map.get(ivec2(x, y));
 
             match v {
@@ -262,29 +179,44 @@ pub fn b(input: &str) -> i32 {
                     map.set(ivec2(2 * x, y), b'[');
                     map.set(ivec2(2 * x + 1, y), b']');
                 }
-                b'@' => pos = ivec2(2 * x, y),
                 _ => continue,
             }
         }
     }
 
-    for instruction in instructions.as_bytes() {
-        let dir = match *instruction {
-            b'<' => ivec2(-1, 0),
-            b'>' => ivec2(1, 0),
-            b'^' => ivec2(0, -1),
-            b'v' => ivec2(0, 1),
-            _ => continue,
+    Ok((map, ivec2(2 * robot.x, robot.y), instructions))
+}
+
+/// Moves the robot, calling `on_move` with the map and the robot after each instruction.
+fn push_boxes(
+    map: &mut Grid<u8>,
+    mut pos: IVec2,
+    instructions: &[u8],
+    mut on_move: impl FnMut(&Grid<u8>, IVec2) -> Result<()>,
+) -> Result<()> {
+    for instruction in instructions {
+        let Some(dir) = Dir4::from_arrow(*instruction).map(Dir4::offset) else {
+            continue;
         };
 
         let new_pos = pos + dir;
-        let ok_move = resolve_collision_b(&mut map, new_pos, dir, None, true);
+        let ok_move = resolve_collision_b(map, new_pos, dir, None, true);
 
         if ok_move {
             pos = new_pos;
         }
+
+        on_move(map, pos)?;
     }
 
+    Ok(())
+}
+
+pub fn b(input: &str) -> Result<i32> {
+    let (mut map, pos, instructions) = wide_map(input)?;
+
+    push_boxes(&mut map, pos, &instructions, |_, _| Ok(()))?;
+
     let mut score = 0;
 
     for y in 0..map.height {
@@ -295,13 +227,71 @@ pub fn b(input: &str) -> i32 {
         }
     }
 
-    score
+    Ok(score)
+}
+
+/// A frame per instruction of part b, with the boxes that just moved highlighted.
+pub fn render(input: &str, sink: &mut Sink) -> Result<()> {
+    let (mut map, pos, instructions) = wide_map(input)?;
+
+    let mut previous = map.clone();
+    sink(Frame::chars(&map).with_highlight([pos], b'@', Rgb::YELLOW))?;
+
+    push_boxes(&mut map, pos, &instructions, |map, pos| {
+        let moved = map
+            .iter()
+            .filter(|&(p, &v)| matches!(v, b'[' | b']') && previous[p] != v)
+            .map(|(p, _)| p);
+
+        sink(
+            Frame::chars(map)
+                .with_background(moved, Rgb::MAGENTA)
+                .with_highlight([pos], b'@', Rgb::YELLOW),
+        )?;
+
+        previous.clone_from(map);
+        Ok(())
+    })
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT_3), 618);
-    assert_eq!(b(TEST_INPUT_2), 1751);
-    assert_eq!(b(TEST_INPUT), 9021);
-    assert_eq!(b(INPUT), 1521453);
+    assert_eq!(b(TEST_INPUT_3).unwrap(), 618);
+    assert_eq!(b(TEST_INPUT_2).unwrap(), 1751);
+    assert_eq!(b(TEST_INPUT).unwrap(), 9021);
+    assert_eq!(
+        b("#######\n#...O.#\n#.@...#\n#######\n\n>>\n").unwrap(),
+        108
+    );
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 1521453);
+}
+
+#[test]
+fn test_render() {
+    let moves = TEST_INPUT_3.bytes().filter(|b| b"<>^v".contains(b)).count();
+
+    let mut frames = 0;
+    render(TEST_INPUT_3, &mut |_| {
+        frames += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(frames, moves + 1);
+
+    // A failing sink stops the render.
+    let mut frames = 0;
+    let res = render(TEST_INPUT_3, &mut |_| {
+        frames += 1;
+        Err(AocError::new("full"))
+    });
+    assert_eq!(res.unwrap_err().to_string(), "full");
+    assert_eq!(frames, 1);
+}
+
+#[test]
+fn test_no_robot() {
+    let input = "####\n#.O#\n####\n\n<\n";
+    assert_eq!(a(input).unwrap_err().to_string(), "no robot '@' in map");
+    assert_eq!(b(input).unwrap_err().to_string(), "no robot '@' in map");
 }
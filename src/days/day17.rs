@@ -1,44 +1,53 @@
+use crate::{
+    error::{self, AocError, Result},
+    parse,
+};
 use std::fmt::Debug;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/17.txt");
 pub static TEST_INPUT: &str = include_str!("../input/17_test.txt");
 pub static TEST_INPUT_2: &str = include_str!("../input/17_test_2.txt");
 pub static TEST_INPUT_3: &str = include_str!("../input/17_test_3.txt");
 pub static TEST_INPUT_4: &str = include_str!("../input/17_test_4.txt");
 
-fn parse(input: &str) -> (Machine, Vec<u8>) {
-    let (machine_str, program_str) = input.split_once("\n\n").unwrap();
+pub(crate) fn parse(input: &str) -> Result<(Machine, Vec<u8>)> {
+    let (machine_str, program_str) = parse::two_sections(input)?;
     let mut lines = machine_str.lines();
+    let mut register = |prefix| {
+        let line = lines.next().unwrap_or(error::end(machine_str));
+        error::parse(input, error::strip_prefix(input, line, prefix)?)
+    };
+
+    let machine = Machine {
+        a: register("Register A: ")?,
+        b: register("Register B: ")?,
+        c: register("Register C: ")?,
+        ip: 0,
+    };
+
+    let values = error::strip_prefix(input, program_str, "Program: ")?;
+    let program = parse::list::<u8>(input, values, ",")?;
+
+    if let Some(i) = program.iter().position(|&n| n > 7) {
+        let at = values.split(',').nth(i).unwrap();
+        return Err(AocError::at(
+            input,
+            at,
+            format!("expected a 3-bit number, found '{}'", program[i]),
+        ));
+    }
 
-    (
-        Machine {
-            a: lines
-                .next()
-                .unwrap()
-                .trim_start_matches("Register A: ")
-                .parse()
-                .unwrap(),
-            b: lines
-                .next()
-                .unwrap()
-                .trim_start_matches("Register B: ")
-                .parse()
-                .unwrap(),
-            c: lines
-                .next()
-                .unwrap()
-                .trim_start_matches("Register C: ")
-                .parse()
-                .unwrap(),
-            ip: 0,
-        },
-        program_str
-            .trim()
-            .trim_start_matches("Program: ")
-            .split(',')
-            .map(|i| i.parse::<u8>().unwrap())
-            .collect::<Vec<_>>(),
-    )
+    // Jumps to odd addresses can still reach a 7 that isn't checked here, `run` catches those.
+    for i in (0..program.len().saturating_sub(1)).step_by(2) {
+        if program[i + 1] == 7 && Instruction::try_from(program[i]).is_ok_and(Instruction::is_combo)
+        {
+            let at = values.split(',').nth(i + 1).unwrap();
+            return Err(AocError::at(input, at, "the combo operand 7 is reserved"));
+        }
+    }
+
+    Ok((machine, program))
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -72,8 +81,21 @@ impl TryFrom<u8> for Instruction {
     }
 }
 
+impl Instruction {
+    fn is_combo(self) -> bool {
+        matches!(
+            self,
+            Instruction::Adv
+                | Instruction::Bst
+                | Instruction::Out
+                | Instruction::Bdv
+                | Instruction::Cdv
+        )
+    }
+}
+
 #[derive(Copy, Clone)]
-struct Machine {
+pub(crate) struct Machine {
     a: u64,
     b: u64,
     c: u64,
@@ -92,24 +114,28 @@ impl Debug for Machine {
 }
 
 impl Machine {
-    fn combo(&self, op: u8) -> u64 {
+    fn combo(&self, op: u8) -> Result<u64> {
         match op {
-            0..=3 => op as u64,
-            4 => self.a,
-            5 => self.b,
-            6 => self.c,
-            _ => panic!("Invalid instruction"),
+            0..=3 => Ok(op as u64),
+            4 => Ok(self.a),
+            5 => Ok(self.b),
+            6 => Ok(self.c),
+            _ => Err(AocError::new(format!(
+                "the combo operand {op} at address {} is reserved",
+                self.ip + 1
+            ))),
         }
     }
 
-    fn run(&mut self, out: &mut Vec<u8>, program: &[u8], mut break_on_out: usize) {
+    fn run(&mut self, out: &mut Vec<u8>, program: &[u8], mut break_on_out: usize) -> Result<()> {
         while self.ip + 1 < program.len() {
-            let ins = Instruction::try_from(program[self.ip]).unwrap();
+            let ins = Instruction::try_from(program[self.ip])
+                .map_err(|_| AocError::new(format!("invalid opcode {}", program[self.ip])))?;
             let op = program[self.ip + 1];
 
             match ins {
                 Instruction::Adv => {
-                    self.a >>= self.combo(op);
+                    self.a >>= self.combo(op)?;
                     self.ip += 2;
                 }
                 Instruction::Bxl => {
@@ -117,7 +143,7 @@ impl Machine {
                     self.ip += 2;
                 }
                 Instruction::Bst => {
-                    self.b = self.combo(op) & 0b111;
+                    self.b = self.combo(op)? & 0b111;
                     self.ip += 2;
                 }
                 Instruction::Jnz => {
@@ -132,24 +158,26 @@ impl Machine {
                     self.ip += 2;
                 }
                 Instruction::Out => {
-                    out.push((self.combo(op) & 0b111) as u8);
+                    out.push((self.combo(op)? & 0b111) as u8);
                     self.ip += 2;
 
                     if break_on_out == 0 {
-                        return;
+                        return Ok(());
                     }
                     break_on_out -= 1;
                 }
                 Instruction::Bdv => {
-                    self.b = self.a >> self.combo(op);
+                    self.b = self.a >> self.combo(op)?;
                     self.ip += 2;
                 }
                 Instruction::Cdv => {
-                    self.c = self.a >> self.combo(op);
+                    self.c = self.a >> self.combo(op)?;
                     self.ip += 2;
                 }
             }
         }
+
+        Ok(())
     }
 }
 
@@ -166,7 +194,9 @@ fn test_machine() {
 
         let program = vec![2, 6];
 
-        machine.run(&mut out, program.as_slice(), usize::MAX);
+        machine
+            .run(&mut out, program.as_slice(), usize::MAX)
+            .unwrap();
 
         assert_eq!(machine.b, 1);
     }
@@ -181,7 +211,9 @@ fn test_machine() {
         };
         let program = vec![5, 0, 5, 1, 5, 4];
 
-        machine.run(&mut out, program.as_slice(), usize::MAX);
+        machine
+            .run(&mut out, program.as_slice(), usize::MAX)
+            .unwrap();
 
         assert_eq!(&out, &[0, 1, 2]);
     }
@@ -196,7 +228,9 @@ fn test_machine() {
         };
         let program = vec![0, 1, 5, 4, 3, 0];
 
-        machine.run(&mut out, program.as_slice(), usize::MAX);
+        machine
+            .run(&mut out, program.as_slice(), usize::MAX)
+            .unwrap();
 
         assert_eq!(&out, &[4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
         assert_eq!(machine.a, 0);
@@ -212,7 +246,9 @@ fn test_machine() {
         };
         let program = vec![1, 7];
 
-        machine.run(&mut out, program.as_slice(), usize::MAX);
+        machine
+            .run(&mut out, program.as_slice(), usize::MAX)
+            .unwrap();
 
         assert_eq!(machine.b, 26);
     }
@@ -227,70 +263,108 @@ fn test_machine() {
         };
         let program = vec![4, 0];
 
-        machine.run(&mut out, program.as_slice(), usize::MAX);
+        machine
+            .run(&mut out, program.as_slice(), usize::MAX)
+            .unwrap();
 
         assert_eq!(machine.b, 44354);
     }
 }
 
-pub fn a(input: &str) -> String {
-    let (mut machine, program) = parse(input);
+pub fn a(input: &str) -> Result<String> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a((mut machine, program): (Machine, Vec<u8>)) -> Result<String> {
     let mut out = Vec::new();
 
-    machine.run(&mut out, &program, usize::MAX);
+    machine.run(&mut out, &program, usize::MAX)?;
 
     let out = out.iter().map(|n| format!("{n}")).collect::<Vec<_>>();
-    out.join(",")
+    Ok(out.join(","))
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), "4,6,3,5,6,3,5,2,1,0");
-    assert_eq!(a(TEST_INPUT_2), "2,7,6,5,6,0,2,3,1");
-    assert_eq!(a(INPUT), "7,5,4,3,4,5,3,4,6");
+    assert_eq!(a(TEST_INPUT).unwrap(), "4,6,3,5,6,3,5,2,1,0");
+    assert_eq!(a(TEST_INPUT_2).unwrap(), "2,7,6,5,6,0,2,3,1");
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), "7,5,4,3,4,5,3,4,6");
 }
 
-fn test(machine: Machine, program: &[u8], a: u64) -> Vec<u8> {
+fn test(machine: Machine, program: &[u8], a: u64) -> Result<Vec<u8>> {
     let mut out = Vec::new();
 
     let mut machine = machine;
     machine.a = a;
 
-    machine.run(&mut out, program, usize::MAX);
+    machine.run(&mut out, program, usize::MAX)?;
 
-    out
+    Ok(out)
 }
 
-fn search(machine: Machine, program: &[u8], index: usize, res: u64) -> u64 {
+fn search(machine: Machine, program: &[u8], index: usize, res: u64) -> Result<u64> {
     for a in 0..=7 {
         let a = a << (index * 3);
 
-        let out = test(machine, program, res | a);
+        let out = test(machine, program, res | a)?;
 
         if out.len() == program.len() && out[index] == program[index] {
             if index > 0 {
-                let answer = search(machine, program, index - 1, res | a);
+                let answer = search(machine, program, index - 1, res | a)?;
                 if answer > 0 {
-                    return answer;
+                    return Ok(answer);
                 }
             } else {
-                return res | a;
+                return Ok(res | a);
             }
         }
     }
 
-    0
+    Ok(0)
 }
 
-pub fn b(input: &str) -> u64 {
-    let (machine, program) = parse(input);
+pub fn b(input: &str) -> Result<u64> {
+    solve_b(parse(input)?)
+}
 
+pub(crate) fn solve_b((machine, program): (Machine, Vec<u8>)) -> Result<u64> {
     search(machine, &program, program.len() - 1, 0)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT_3), 117440);
-    assert_eq!(b(INPUT), 164278899142333);
-    assert_eq!(b(TEST_INPUT_4), 107416870455451);
+    assert_eq!(b(TEST_INPUT_3).unwrap(), 117440);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 164278899142333);
+    assert_eq!(b(TEST_INPUT_4).unwrap(), 107416870455451);
+}
+
+#[test]
+fn test_parse_error() {
+    assert_eq!(
+        a("Register A: 1\nRegister B: 0\nRegister D: 0\n\nProgram: 0,1")
+            .unwrap_err()
+            .to_string(),
+        "line 3, column 1: expected 'Register C: '"
+    );
+    assert_eq!(
+        a("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,8")
+            .unwrap_err()
+            .to_string(),
+        "line 5, column 12: expected a 3-bit number, found '8'"
+    );
+    assert_eq!(
+        a("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 1,7,5,7")
+            .unwrap_err()
+            .to_string(),
+        "line 5, column 16: the combo operand 7 is reserved"
+    );
+    // Only reached through the jump to 3, where 7 is the operand of `out`.
+    assert_eq!(
+        a("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,3,1,5,7,0")
+            .unwrap_err()
+            .to_string(),
+        "the combo operand 7 at address 4 is reserved"
+    );
 }
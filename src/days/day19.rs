@@ -1,5 +1,10 @@
+use crate::{
+    error::{AocError, Result},
+    parse,
+};
 use aho_corasick::AhoCorasick;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/19.txt");
 pub static TEST_INPUT: &str = include_str!("../input/19_test.txt");
 
@@ -24,8 +29,8 @@ fn match_pattern(patterns: &[&str], design: &str) -> bool {
     false
 }
 
-pub fn a(input: &str) -> i32 {
-    let (pattern_str, design_str) = input.trim().split_once("\n\n").unwrap();
+pub fn a(input: &str) -> Result<i32> {
+    let (pattern_str, design_str) = parse::two_sections(input)?;
 
     let mut patterns = pattern_str.split(", ").collect::<Vec<_>>();
     patterns.sort_by_key(|a| a.len());
@@ -38,17 +43,18 @@ pub fn a(input: &str) -> i32 {
         }
     }
 
-    possible_designs
+    Ok(possible_designs)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 6);
-    assert_eq!(a(INPUT), 363);
+    assert_eq!(a(TEST_INPUT).unwrap(), 6);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 363);
 }
 
-pub fn b(input: &str) -> i64 {
-    let (pattern_str, design_str) = input.trim().split_once("\n\n").unwrap();
+pub fn b(input: &str) -> Result<i64> {
+    let (pattern_str, design_str) = parse::two_sections(input)?;
 
     let patterns = pattern_str
         .split(", ")
@@ -57,7 +63,7 @@ pub fn b(input: &str) -> i64 {
 
     let mut possible_patterns = 0;
 
-    let ac = AhoCorasick::new(patterns).unwrap();
+    let ac = AhoCorasick::new(patterns).map_err(|e| AocError::new(e.to_string()))?;
 
     let mut hits = Vec::new();
 
@@ -74,11 +80,12 @@ pub fn b(input: &str) -> i64 {
         possible_patterns += hits[design.len()];
     }
 
-    possible_patterns
+    Ok(possible_patterns)
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT), 16);
-    assert_eq!(b(INPUT), 642535800868438);
+    assert_eq!(b(TEST_INPUT).unwrap(), 16);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), 642535800868438);
 }
@@ -1,199 +1,35 @@
+use crate::{
+    dir::neighbours4,
+    error::{self, AocError, Result},
+    outln, search,
+};
 use cached::proc_macro::cached;
 use glam::{ivec2, IVec2};
 use itertools::Itertools;
-use smallvec::SmallVec;
-use std::{cmp, collections::BinaryHeap, str};
+use std::str;
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/21.txt");
 pub static TEST_INPUT: &str = include_str!("../input/21_test.txt");
 
-pub trait MapDefault {
-    fn map_default() -> Self;
-}
-
-impl MapDefault for bool {
-    fn map_default() -> Self {
-        false
-    }
-}
-
-impl MapDefault for u8 {
-    fn map_default() -> Self {
-        b'.'
-    }
-}
-
-impl MapDefault for u32 {
-    fn map_default() -> Self {
-        u32::MAX
-    }
-}
-
-impl MapDefault for IVec2 {
-    fn map_default() -> Self {
-        ivec2(0, 0)
-    }
-}
-
-#[derive(Clone)]
-pub struct Map<T>
-where
-    T: Copy + Clone + MapDefault,
-{
-    data: Vec<T>,
-    width: i32,
-    height: i32,
-}
-
-impl<T> Map<T>
-where
-    T: Copy + Clone + MapDefault,
-{
-    pub fn empty(width: i32, height: i32, initial: T) -> Map<T> {
-        Map {
-            data: vec![initial; (width * height) as usize],
-            width,
-            height,
-        }
-    }
-
-    pub fn get(&self, pos: IVec2) -> T {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return T::map_default();
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return T::map_default();
-        }
-
-        self.data[index as usize]
-    }
-
-    pub fn set(&mut self, pos: IVec2, new: T) -> bool {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            return false;
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            return false;
-        }
-
-        self.data[index as usize] = new;
-
-        true
-    }
-}
-
-struct CameFrom {
-    data: Vec<SmallVec<[IVec2; 2]>>,
-    width: i32,
-    height: i32,
-}
-
-impl CameFrom {
-    fn empty(width: i32, height: i32) -> CameFrom {
-        CameFrom {
-            data: vec![SmallVec::new(); (width * height) as usize],
-            width,
-            height,
-        }
-    }
-
-    fn get_mut(&mut self, pos: IVec2) -> &mut SmallVec<[IVec2; 2]> {
-        let index = pos.x + pos.y * self.width;
-
-        if pos.x < 0 || pos.x >= self.width {
-            panic!();
-        }
-
-        if pos.y < 0 || pos.y >= self.height {
-            panic!();
-        }
-
-        &mut self.data[index as usize]
-    }
-}
-
-struct Cost {
-    pos: IVec2,
-    cost: u32,
-}
-
-impl PartialEq for Cost {
-    fn eq(&self, other: &Self) -> bool {
-        self.cost.eq(&other.cost)
-    }
-}
-
-impl Eq for Cost {}
-
-impl PartialOrd for Cost {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Cost {
-    fn cmp(&self, other: &Self) -> cmp::Ordering {
-        other.cost.cmp(&self.cost)
-    }
-}
-
-fn all_paths(came_from: &mut CameFrom, current: IVec2, start: IVec2) -> Vec<Vec<IVec2>> {
-    let mut paths = Vec::new();
-    paths.push(vec![current]);
-
-    loop {
-        let mut new_paths = Vec::new();
-
-        let mut did_work = false;
-
-        for path in &mut paths {
-            let current = path.last().unwrap();
-
-            if *current == start {
-                continue;
-            }
-
-            did_work = true;
-            let came_from = came_from.get_mut(*current);
-
-            #[allow(clippy::comparison_chain)]
-            if came_from.len() == 1 {
-                path.push(came_from[0]);
-            } else if came_from.len() > 1 {
-                let old = path.clone();
-
-                for (i, next) in came_from.iter().enumerate() {
-                    if i == 0 {
-                        path.push(*next);
-                    } else {
-                        let mut new_path = old.clone();
-                        new_path.push(*next);
-                        new_paths.push(new_path);
-                    }
-                }
-            }
-        }
-
-        for new_path in new_paths {
-            paths.push(new_path);
-        }
-
-        if !did_work {
-            break;
-        }
-    }
-
-    for path in &mut paths {
-        path.reverse();
-    }
-
-    paths
+/// Every shortest path from `start` to `end` on a keypad of `size` with a gap at `gap`.
+fn all_paths(start: IVec2, end: IVec2, size: IVec2, gap: IVec2) -> Vec<Vec<IVec2>> {
+    let paths = search::dijkstra_all(
+        start,
+        |&current| {
+            neighbours4(current)
+                .filter(|neighbor| {
+                    (0..size.x).contains(&neighbor.x)
+                        && (0..size.y).contains(&neighbor.y)
+                        && *neighbor != gap
+                })
+                .map(|neighbor| (neighbor, 1))
+        },
+        |&current| current == end,
+    )
+    .expect("No path");
+
+    paths.paths().collect()
 }
 
 fn pos_from_digit(digit: u8) -> IVec2 {
@@ -218,54 +54,7 @@ fn pos_from_digit(digit: u8) -> IVec2 {
 
 #[cached]
 fn path_numpad(start: IVec2, end: IVec2) -> Vec<Vec<IVec2>> {
-    let mut g_score = Map::<u32>::empty(3, 4, u32::MAX);
-    g_score.set(start, 0);
-
-    let mut open_set = BinaryHeap::new();
-    open_set.push(Cost {
-        pos: start,
-        cost: 0,
-    });
-
-    let mut came_from = CameFrom::empty(3, 4);
-
-    while let Some(Cost { pos: current, .. }) = open_set.pop() {
-        if current == end {
-            return all_paths(&mut came_from, current, start);
-        }
-
-        for neighbor_dir in [ivec2(1, 0), ivec2(-1, 0), ivec2(0, 1), ivec2(0, -1)] {
-            let neighbor = current + neighbor_dir;
-
-            if !((0..3).contains(&neighbor.x)
-                && (0..4).contains(&neighbor.y)
-                && neighbor != ivec2(0, 3))
-            {
-                continue;
-            }
-
-            let tentative_g_score = g_score.get(current) + 1;
-            let neighbor_g_score = g_score.get(neighbor);
-            if tentative_g_score <= neighbor_g_score {
-                let came_from = came_from.get_mut(neighbor);
-                if tentative_g_score < neighbor_g_score {
-                    came_from.clear();
-                }
-                came_from.push(current);
-
-                if tentative_g_score < neighbor_g_score {
-                    g_score.set(neighbor, tentative_g_score);
-
-                    open_set.push(Cost {
-                        pos: neighbor,
-                        cost: tentative_g_score,
-                    });
-                }
-            }
-        }
-    }
-
-    panic!("No path");
+    all_paths(start, end, ivec2(3, 4), ivec2(0, 3))
 }
 
 fn map_pair_to_dir(a: IVec2, b: IVec2) -> char {
@@ -318,54 +107,7 @@ fn pos_from_dir(dir: char) -> IVec2 {
 
 #[cached]
 fn path_keypad(start: IVec2, end: IVec2) -> Vec<Vec<IVec2>> {
-    let mut g_score = Map::<u32>::empty(3, 2, u32::MAX);
-    g_score.set(start, 0);
-
-    let mut open_set = BinaryHeap::new();
-    open_set.push(Cost {
-        pos: start,
-        cost: 0,
-    });
-
-    let mut came_from = CameFrom::empty(3, 2);
-
-    while let Some(Cost { pos: current, .. }) = open_set.pop() {
-        if current == end {
-            return all_paths(&mut came_from, current, start);
-        }
-
-        for neighbor_dir in [ivec2(1, 0), ivec2(-1, 0), ivec2(0, 1), ivec2(0, -1)] {
-            let neighbor = current + neighbor_dir;
-
-            if !((0..3).contains(&neighbor.x)
-                && (0..2).contains(&neighbor.y)
-                && neighbor != ivec2(0, 0))
-            {
-                continue;
-            }
-
-            let tentative_g_score = g_score.get(current) + 1;
-            let neighbor_g_score = g_score.get(neighbor);
-            if tentative_g_score <= neighbor_g_score {
-                let came_from = came_from.get_mut(neighbor);
-                if tentative_g_score < neighbor_g_score {
-                    came_from.clear();
-                }
-                came_from.push(current);
-
-                if tentative_g_score < neighbor_g_score {
-                    g_score.set(neighbor, tentative_g_score);
-
-                    open_set.push(Cost {
-                        pos: neighbor,
-                        cost: tentative_g_score,
-                    });
-                }
-            }
-        }
-    }
-
-    panic!("No path");
+    all_paths(start, end, ivec2(3, 2), ivec2(0, 0))
 }
 
 fn find_all_keypad_sequences(sequence: String) -> Vec<String> {
@@ -395,29 +137,36 @@ fn find_all_keypad_sequences(sequence: String) -> Vec<String> {
         .collect::<Vec<_>>()
 }
 
-pub fn a(input: &str) -> i32 {
-    let codes = input
+pub(crate) fn parse(input: &str) -> Result<Vec<(&[u8], i32)>> {
+    input
         .trim()
         .lines()
         .map(|s| {
-            (
-                s.as_bytes(),
-                s.trim_end_matches('A').parse::<i32>().unwrap(),
-            )
+            if let Some(i) = s.find(|c: char| !c.is_ascii_digit() && c != 'A') {
+                return Err(AocError::at(input, &s[i..], "expected a numpad key"));
+            }
+
+            Ok((s.as_bytes(), error::parse(input, s.trim_end_matches('A'))?))
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
+pub fn a(input: &str) -> Result<i32> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a(codes: Vec<(&[u8], i32)>) -> Result<i32> {
     let mut sum_of_complexity = 0;
 
     for (code, code_no) in codes {
-        println!("{}", str::from_utf8(code).unwrap());
+        outln!("{}", str::from_utf8(code).unwrap());
 
         let all_numpad_sequences = find_all_numpad_sequences(code);
 
         let mut sequences = all_numpad_sequences;
 
         for i in 0..2 {
-            println!("{i} {} {}", sequences.len(), sequences[0]);
+            outln!("{i} {} {}", sequences.len(), sequences[0]);
             sequences = sequences
                 .into_iter()
                 .flat_map(find_all_keypad_sequences)
@@ -431,7 +180,7 @@ pub fn a(input: &str) -> i32 {
         sum_of_complexity += code_no * sequences.iter().map(|s| s.len()).min().unwrap() as i32;
     }
 
-    sum_of_complexity
+    Ok(sum_of_complexity)
 }
 
 #[test]
@@ -441,29 +190,22 @@ fn test_a() {
     //assert_eq!(a(INPUT), 237342);
 }
 
-pub fn b(input: &str) -> i32 {
-    let codes = input
-        .trim()
-        .lines()
-        .map(|s| {
-            (
-                s.as_bytes(),
-                s.trim_end_matches('A').parse::<i32>().unwrap(),
-            )
-        })
-        .collect::<Vec<_>>();
+pub fn b(input: &str) -> Result<i32> {
+    solve_b(parse(input)?)
+}
 
+pub(crate) fn solve_b(codes: Vec<(&[u8], i32)>) -> Result<i32> {
     let mut sum_of_complexity = 0;
 
     for (code, code_no) in codes {
-        println!("{}", str::from_utf8(code).unwrap());
+        outln!("{}", str::from_utf8(code).unwrap());
 
         let all_numpad_sequences = find_all_numpad_sequences(code);
 
         let mut sequences = all_numpad_sequences;
 
         for i in 0..25 {
-            println!("{i} {} {}", sequences.len(), sequences[0]);
+            outln!("{i} {} {}", sequences.len(), sequences[0]);
             sequences = sequences
                 .into_iter()
                 .flat_map(find_all_keypad_sequences)
@@ -473,7 +215,7 @@ pub fn b(input: &str) -> i32 {
         sum_of_complexity += code_no * sequences.iter().map(|s| s.len()).min().unwrap() as i32;
     }
 
-    sum_of_complexity
+    Ok(sum_of_complexity)
 }
 
 #[test]
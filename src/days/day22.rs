@@ -1,5 +1,9 @@
-use crate::{AdventHashMap, AdventHashSet};
+use crate::{
+    error::{self, Result},
+    AdventHashMap, AdventHashSet,
+};
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/22.txt");
 pub static TEST_INPUT: &str = include_str!("../input/22_test.txt");
 pub static TEST_INPUT_2: &str = include_str!("../input/22_test_2.txt");
@@ -12,11 +16,19 @@ fn prune(a: i64) -> i64 {
     a & (16777216 - 1)
 }
 
-pub fn a(input: &str) -> i64 {
+pub(crate) fn parse(input: &str) -> Result<Vec<i64>> {
+    input.lines().map(|l| error::parse(input, l)).collect()
+}
+
+pub fn a(input: &str, iterations: u32) -> Result<i64> {
+    solve_a(parse(input)?, iterations)
+}
+
+pub(crate) fn solve_a(values: Vec<i64>, iterations: u32) -> Result<i64> {
     let mut sum_of_secret_numbers = 0;
 
-    for mut value in input.lines().map(|l| l.parse::<i64>().unwrap()) {
-        for _ in 0..2000 {
+    for mut value in values {
+        for _ in 0..iterations {
             value = prune(mix(value, value << 6));
             value = prune(mix(value, value >> 5));
             value = prune(mix(value, value << 11));
@@ -25,25 +37,30 @@ pub fn a(input: &str) -> i64 {
         sum_of_secret_numbers += value;
     }
 
-    sum_of_secret_numbers
+    Ok(sum_of_secret_numbers)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 37327623);
-    assert_eq!(a(INPUT), 14726157693);
+    assert_eq!(a(TEST_INPUT, 2000).unwrap(), 37327623);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT, 2000).unwrap(), 14726157693);
+}
+
+pub fn b(input: &str, iterations: u32) -> Result<i32> {
+    solve_b(parse(input)?, iterations)
 }
 
-pub fn b(input: &str) -> i32 {
+pub(crate) fn solve_b(values: Vec<i64>, iterations: u32) -> Result<i32> {
     let mut price_tables = Vec::new();
 
-    for mut value in input.lines().map(|l| l.parse::<i64>().unwrap()) {
+    for mut value in values {
         let mut price_table = AdventHashMap::default();
 
         let mut last_price = value % 10;
         let mut diffs = [0i8; 4];
 
-        for i in 0..2000 {
+        for i in 0..iterations {
             value = prune(mix(value, value << 6));
             value = prune(mix(value, value >> 5));
             value = prune(mix(value, value << 11));
@@ -67,7 +84,7 @@ pub fn b(input: &str) -> i32 {
         .flat_map(|t| t.keys())
         .collect::<AdventHashSet<_>>();
 
-    possible_sequences
+    Ok(possible_sequences
         .into_iter()
         .map(|s| {
             price_tables
@@ -76,11 +93,12 @@ pub fn b(input: &str) -> i32 {
                 .sum::<i32>()
         })
         .max()
-        .unwrap_or(0)
+        .unwrap_or(0))
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(TEST_INPUT_2), 23);
-    assert_eq!(b(INPUT), 1614);
+    assert_eq!(b(TEST_INPUT_2, 2000).unwrap(), 23);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT, 2000).unwrap(), 1614);
 }
@@ -1,9 +1,13 @@
 #![allow(clippy::collapsible_if)]
 
-use crate::{AdventHashMap, AdventHashSet};
+use crate::{
+    error::{self, AocError, Result},
+    outln, parse, span, AdventHashMap, AdventHashSet,
+};
 use itertools::Itertools;
 use std::{cmp::Ordering, mem, ops::RangeInclusive};
 
+#[cfg(feature = "embedded-inputs")]
 pub static INPUT: &str = include_str!("../input/24.txt");
 pub static TEST_INPUT: &str = include_str!("../input/24_test.txt");
 pub static TEST_INPUT_2: &str = include_str!("../input/24_test_2.txt");
@@ -16,7 +20,7 @@ enum GateOp {
 }
 
 #[derive(Clone, Copy)]
-struct Gate<'a> {
+pub(crate) struct Gate<'a> {
     op: GateOp,
     in1: &'a str,
     in2: &'a str,
@@ -24,24 +28,53 @@ struct Gate<'a> {
 }
 
 impl<'a> Gate<'a> {
-    fn from_str(s: &'a str) -> Result<Self, ()> {
-        let (input, out) = s.split_once(" -> ").ok_or(())?;
-        let mut input = input.split(' ');
-        let in1 = input.next().ok_or(())?;
-        let op = input.next().ok_or(())?;
-        let in2 = input.next().ok_or(())?;
+    fn from_str(input: &str, s: &'a str) -> Result<Self> {
+        let (gate_input, out) = error::split_once(input, s, " -> ")?;
+        let (in1, rest) = error::split_once(input, gate_input, " ")?;
+        let (op, in2) = error::split_once(input, rest, " ")?;
 
         let op = match op {
             "AND" => GateOp::And,
             "OR" => GateOp::Or,
             "XOR" => GateOp::Xor,
-            _ => Err(())?,
+            _ => {
+                return Err(AocError::at(
+                    input,
+                    op,
+                    format!("expected AND, OR or XOR, found '{op}'"),
+                ))
+            }
         };
 
         Ok(Gate { op, in1, in2, out })
     }
 }
 
+pub(crate) type Wires<'a> = AdventHashMap<&'a str, u8>;
+pub(crate) type Gates<'a> = AdventHashMap<&'a str, Gate<'a>>;
+
+pub(crate) fn parse(input: &str) -> Result<(Wires<'_>, Gates<'_>)> {
+    let (wires, gates) = parse::two_sections(input)?;
+
+    let wires = wires
+        .lines()
+        .map(|l| {
+            let (name, signal) = error::split_once(input, l, ": ")?;
+            Ok((name, error::parse::<u8>(input, signal)?))
+        })
+        .collect::<Result<Wires>>()?;
+
+    let gates = gates
+        .lines()
+        .map(|l| {
+            let g = Gate::from_str(input, l)?;
+            Ok((g.out, g))
+        })
+        .collect::<Result<Gates>>()?;
+
+    Ok((wires, gates))
+}
+
 fn resolve_gate(
     gates: &AdventHashMap<&str, Gate>,
     wires: &AdventHashMap<&str, u8>,
@@ -66,22 +99,12 @@ fn resolve_gate(
     })
 }
 
-pub fn a(input: &str) -> u64 {
-    let (wires, gates) = input.split_once("\n\n").unwrap();
+pub fn a(input: &str) -> Result<u64> {
+    solve_a(span::time("parse", || parse(input))?)
+}
 
-    let wires = wires
-        .lines()
-        .map(|l| l.split_once(": ").unwrap())
-        .map(|(name, signal)| (name, signal.parse::<u8>().unwrap()))
-        .collect::<AdventHashMap<_, _>>();
-
-    let gates = gates
-        .lines()
-        .map(|l| {
-            let g = Gate::from_str(l).unwrap();
-            (g.out, g)
-        })
-        .collect::<AdventHashMap<_, _>>();
+pub(crate) fn solve_a((wires, gates): (Wires<'_>, Gates<'_>)) -> Result<u64> {
+    let _span = span::enter("resolve");
 
     let mut num = 0;
 
@@ -92,19 +115,31 @@ pub fn a(input: &str) -> u64 {
             break;
         };
 
-        let bit = resolve_gate(&gates, &wires, gate).unwrap();
+        let bit = resolve_gate(&gates, &wires, gate)
+            .ok_or_else(|| AocError::new(format!("could not resolve '{name}'")))?;
 
         num |= (bit as u64) << i;
     }
 
-    num
+    Ok(num)
 }
 
 #[test]
 fn test_a() {
-    assert_eq!(a(TEST_INPUT), 4);
-    assert_eq!(a(TEST_INPUT_2), 2024);
-    assert_eq!(a(INPUT), 36902370467952);
+    assert_eq!(a(TEST_INPUT).unwrap(), 4);
+    assert_eq!(a(TEST_INPUT_2).unwrap(), 2024);
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(a(INPUT).unwrap(), 36902370467952);
+}
+
+#[test]
+fn test_parse_error() {
+    assert_eq!(
+        a("x00: 1\ny00: 0\n\nx00 NAND y00 -> z00")
+            .unwrap_err()
+            .to_string(),
+        "line 4, column 5: expected AND, OR or XOR, found 'NAND'"
+    );
 }
 
 fn fetch_gates<'a>(
@@ -197,7 +232,7 @@ fn has_loop(
     let mut gates = gates.clone();
 
     {
-        let [a, b] = gates.get_many_mut([a_str, b_str]);
+        let [a, b] = gates.get_disjoint_mut([a_str, b_str]);
         mem::swap(a.unwrap(), b.unwrap());
     }
 
@@ -226,7 +261,7 @@ fn test_combination(
             return false;
         }
 
-        let [a, b] = gates.get_many_mut([a, b]);
+        let [a, b] = gates.get_disjoint_mut([a, b]);
         mem::swap(a.unwrap(), b.unwrap());
     }
 
@@ -258,7 +293,7 @@ fn test_combinations(
             return false;
         }
 
-        let [a, b] = gates.get_many_mut([a, b]);
+        let [a, b] = gates.get_disjoint_mut([a, b]);
         mem::swap(a.unwrap(), b.unwrap());
     }
 
@@ -348,7 +383,7 @@ fn find_bad_gates<'a>(
             *should_be_2,
         ) {
             if swapped_gates.len() <= 2 {
-                println!("{swapped:?}: {}", possible_pairs.len());
+                outln!("{swapped:?}: {}", possible_pairs.len());
             }
 
             let found = find_bad_gates(
@@ -382,22 +417,12 @@ fn find_bad_gates<'a>(
     )
 }
 
-pub fn b(input: &str) -> String {
-    let (wires, gates) = input.split_once("\n\n").unwrap();
-
-    let wires = wires
-        .lines()
-        .map(|l| l.split_once(": ").unwrap())
-        .map(|(name, signal)| (name, signal.parse::<u8>().unwrap()))
-        .collect::<AdventHashMap<_, _>>();
+pub fn b(input: &str) -> Result<String> {
+    solve_b(span::time("parse", || parse(input))?)
+}
 
-    let gates = gates
-        .lines()
-        .map(|l| {
-            let g = Gate::from_str(l).unwrap();
-            (g.out, g)
-        })
-        .collect::<AdventHashMap<_, _>>();
+pub(crate) fn solve_b((wires, gates): (Wires<'_>, Gates<'_>)) -> Result<String> {
+    let build = span::enter("build");
 
     let input_count = (wires.len() / 2) as i32;
     let mut output_count = 0;
@@ -460,7 +485,7 @@ pub fn b(input: &str) -> String {
         })
         .collect::<AdventHashMap<_, _>>();
 
-    println!("{could_affect_output_upto:#?}");
+    outln!("{could_affect_output_upto:#?}");
 
     let mut gates_for_output = AdventHashMap::default();
     let mut outputs_to_correct = Vec::new();
@@ -489,6 +514,9 @@ pub fn b(input: &str) -> String {
     }
 
     outputs_to_correct.reverse();
+    drop(build);
+
+    let _span = span::enter("search");
 
     if let Some(res) = find_bad_gates(
         &could_affect_output_upto,
@@ -501,17 +529,19 @@ pub fn b(input: &str) -> String {
         z,
         z2,
     ) {
-        res.iter()
+        Ok(res
+            .iter()
             .copied()
             .flat_map(|p| vec![p.0, p.1])
             .sorted()
-            .join(",")
+            .join(","))
     } else {
-        panic!("Did not find answer");
+        Err(AocError::new("did not find answer"))
     }
 }
 
 #[test]
 fn test_b() {
-    assert_eq!(b(INPUT), "");
+    #[cfg(feature = "embedded-inputs")]
+    assert_eq!(b(INPUT).unwrap(), "");
 }
@@ -0,0 +1,94 @@
+use crate::error::{AocError, Result};
+
+#[cfg(feature = "embedded-inputs")]
+pub static INPUT: &str = include_str!("../input/25.txt");
+pub static TEST_INPUT: &str = include_str!("../input/25_test.txt");
+
+const WIDTH: usize = 5;
+const HEIGHT: usize = 7;
+
+pub(crate) type Heights = [u8; WIDTH];
+
+pub(crate) fn parse(input: &str) -> Result<(Vec<Heights>, Vec<Heights>)> {
+    let mut locks = Vec::new();
+    let mut keys = Vec::new();
+
+    for schematic in input.split("\n\n").filter(|s| !s.trim().is_empty()) {
+        let schematic = schematic.trim_matches('\n');
+        let lines = schematic.lines().collect::<Vec<_>>();
+
+        if lines.len() != HEIGHT || lines.iter().any(|l| l.len() != WIDTH) {
+            return Err(AocError::at(
+                input,
+                schematic,
+                format!("expected a {WIDTH}x{HEIGHT} schematic"),
+            ));
+        }
+
+        let mut heights = [0; WIDTH];
+
+        for line in &lines[1..HEIGHT - 1] {
+            for (x, c) in line.bytes().enumerate() {
+                match c {
+                    b'#' => heights[x] += 1,
+                    b'.' => (),
+                    _ => return Err(AocError::at(input, &line[x..], "expected '#' or '.'")),
+                }
+            }
+        }
+
+        match (lines[0], lines[HEIGHT - 1]) {
+            ("#####", ".....") => locks.push(heights),
+            (".....", "#####") => keys.push(heights),
+            _ => return Err(AocError::at(input, schematic, "expected a lock or a key")),
+        }
+    }
+
+    Ok((locks, keys))
+}
+
+pub fn a(input: &str) -> Result<i32> {
+    solve_a(parse(input)?)
+}
+
+pub(crate) fn solve_a((locks, keys): (Vec<Heights>, Vec<Heights>)) -> Result<i32> {
+    let mut fitting_pairs = 0;
+
+    for lock in &locks {
+        for key in &keys {
+            if lock
+                .iter()
+                .zip(key)
+                .all(|(l, k)| (l + k) as usize <= HEIGHT - 2)
+            {
+                fitting_pairs += 1;
+            }
+        }
+    }
+
+    Ok(fitting_pairs)
+}
+
+#[test]
+fn test_a() {
+    assert_eq!(a(TEST_INPUT).unwrap(), 3);
+}
+
+#[test]
+fn test_parse_error() {
+    let input = TEST_INPUT.replacen(".####", ".##x#", 1);
+
+    assert_eq!(
+        a(&input).unwrap_err().to_string(),
+        "line 2, column 4: expected '#' or '.'"
+    );
+}
+
+/// Day 25 has no second puzzle, the last star is given for finishing the others.
+pub fn b(input: &str) -> Result<i32> {
+    solve_b(parse(input)?)
+}
+
+pub(crate) fn solve_b(_: (Vec<Heights>, Vec<Heights>)) -> Result<i32> {
+    Err(AocError::new("day 25 has no part b"))
+}
@@ -0,0 +1,247 @@
+use glam::{ivec2, IVec2};
+
+/// The four orthogonal directions in clockwise order, with y pointing down.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Dir4 {
+    #[default]
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    /// The position in [`Dir4::ALL`], e.g. to index a [`crate::bitgrid::DirBitGrid`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn offset(self) -> IVec2 {
+        match self {
+            Dir4::Up => ivec2(0, -1),
+            Dir4::Right => ivec2(1, 0),
+            Dir4::Down => ivec2(0, 1),
+            Dir4::Left => ivec2(-1, 0),
+        }
+    }
+
+    pub fn from_offset(offset: IVec2) -> Option<Dir4> {
+        Dir4::ALL.into_iter().find(|d| d.offset() == offset)
+    }
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Dir4 {
+        Dir4::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Mirrored in a vertical line, swapping left and right.
+    pub fn flip_x(self) -> Dir4 {
+        match self {
+            Dir4::Left => Dir4::Right,
+            Dir4::Right => Dir4::Left,
+            d => d,
+        }
+    }
+
+    /// Mirrored in a horizontal line, swapping up and down.
+    pub fn flip_y(self) -> Dir4 {
+        match self {
+            Dir4::Up => Dir4::Down,
+            Dir4::Down => Dir4::Up,
+            d => d,
+        }
+    }
+
+    /// The direction of one of `^>v<`.
+    pub fn from_arrow(c: u8) -> Option<Dir4> {
+        match c {
+            b'^' => Some(Dir4::Up),
+            b'>' => Some(Dir4::Right),
+            b'v' => Some(Dir4::Down),
+            b'<' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> u8 {
+        b"^>v<"[self.index()]
+    }
+
+    pub fn unicode_arrow(self) -> char {
+        ['↑', '→', '↓', '←'][self.index()]
+    }
+}
+
+impl From<Dir4> for IVec2 {
+    fn from(dir: Dir4) -> IVec2 {
+        dir.offset()
+    }
+}
+
+/// The eight directions including diagonals in clockwise order, with y pointing down.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Dir8 {
+    #[default]
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn offset(self) -> IVec2 {
+        match self {
+            Dir8::Up => ivec2(0, -1),
+            Dir8::UpRight => ivec2(1, -1),
+            Dir8::Right => ivec2(1, 0),
+            Dir8::DownRight => ivec2(1, 1),
+            Dir8::Down => ivec2(0, 1),
+            Dir8::DownLeft => ivec2(-1, 1),
+            Dir8::Left => ivec2(-1, 0),
+            Dir8::UpLeft => ivec2(-1, -1),
+        }
+    }
+
+    pub fn from_offset(offset: IVec2) -> Option<Dir8> {
+        Dir8::ALL.into_iter().find(|d| d.offset() == offset)
+    }
+
+    /// Turned 45 degrees clockwise.
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self.index() + 1) % 8]
+    }
+
+    /// Turned 45 degrees counterclockwise.
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Dir8 {
+        Dir8::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn flip_x(self) -> Dir8 {
+        Dir8::ALL[(8 - self.index()) % 8]
+    }
+
+    pub fn flip_y(self) -> Dir8 {
+        Dir8::ALL[(12 - self.index()) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn unicode_arrow(self) -> char {
+        ['↑', '↗', '→', '↘', '↓', '↙', '←', '↖'][self.index()]
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Dir8 {
+        Dir8::ALL[dir.index() * 2]
+    }
+}
+
+impl From<Dir8> for IVec2 {
+    fn from(dir: Dir8) -> IVec2 {
+        dir.offset()
+    }
+}
+
+/// The four orthogonal neighbours of `pos`.
+pub fn neighbours4(pos: IVec2) -> impl Iterator<Item = IVec2> {
+    Dir4::ALL.into_iter().map(move |d| pos + d.offset())
+}
+
+/// The eight neighbours of `pos`, including diagonals.
+pub fn neighbours8(pos: IVec2) -> impl Iterator<Item = IVec2> {
+    Dir8::ALL.into_iter().map(move |d| pos + d.offset())
+}
+
+/// The distance between `a` and `b` moving only horizontally and vertically.
+pub fn manhattan(a: IVec2, b: IVec2) -> u32 {
+    (a.x - b.x).unsigned_abs() + (a.y - b.y).unsigned_abs()
+}
+
+/// The distance between `a` and `b` when diagonal steps are allowed too.
+pub fn chebyshev(a: IVec2, b: IVec2) -> u32 {
+    (a.x - b.x).unsigned_abs().max((a.y - b.y).unsigned_abs())
+}
+
+/// Every offset at most `radius` orthogonal steps away, including zero, column by column.
+pub fn diamond(radius: i32) -> impl Iterator<Item = IVec2> {
+    (-radius..=radius).flat_map(move |x| {
+        let max_y = radius - x.abs();
+        (-max_y..=max_y).map(move |y| ivec2(x, y))
+    })
+}
+
+#[test]
+fn test_dir() {
+    assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+    assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+    assert_eq!(Dir4::Left.reverse(), Dir4::Right);
+    assert_eq!(Dir4::Left.flip_x(), Dir4::Right);
+    assert_eq!(Dir4::Left.flip_y(), Dir4::Left);
+    assert_eq!(Dir4::from_offset(ivec2(0, 1)), Some(Dir4::Down));
+    assert_eq!(Dir4::from_offset(ivec2(1, 1)), None);
+    assert_eq!(Dir4::from_arrow(b'<').map(Dir4::arrow), Some(b'<'));
+
+    for d in Dir4::ALL {
+        assert_eq!(d.turn_right().turn_left(), d);
+        assert_eq!(Dir8::from(d).offset(), d.offset());
+    }
+
+    for d in Dir8::ALL {
+        assert_eq!(d.turn_right().turn_left(), d);
+        assert_eq!(d.reverse().offset(), -d.offset());
+        assert_eq!(d.flip_x().offset(), d.offset() * ivec2(-1, 1));
+        assert_eq!(d.flip_y().offset(), d.offset() * ivec2(1, -1));
+        assert_eq!(Dir8::from_offset(d.offset()), Some(d));
+    }
+
+    assert_eq!(Dir8::UpRight.unicode_arrow(), '↗');
+    assert!(Dir8::DownLeft.is_diagonal());
+}
+
+#[test]
+fn test_distances() {
+    assert_eq!(manhattan(ivec2(1, 2), ivec2(-2, 4)), 5);
+    assert_eq!(chebyshev(ivec2(1, 2), ivec2(-2, 4)), 3);
+    assert_eq!(neighbours4(ivec2(0, 0)).count(), 4);
+    assert!(neighbours8(ivec2(5, 5)).all(|n| chebyshev(n, ivec2(5, 5)) == 1));
+
+    assert_eq!(diamond(0).collect::<Vec<_>>(), [ivec2(0, 0)]);
+    assert_eq!(diamond(2).count(), 13);
+    assert!(diamond(20).all(|d| manhattan(d, IVec2::ZERO) <= 20));
+    assert_eq!(diamond(20).count(), 841);
+}
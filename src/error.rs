@@ -0,0 +1,148 @@
+use std::{
+    any::type_name,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+pub type Result<T, E = AocError> = std::result::Result<T, E>;
+
+/// An error in the puzzle input, with the line and column it was found at when known.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AocError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl AocError {
+    pub fn new(message: impl Into<String>) -> AocError {
+        AocError {
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// An error pointing at `at`, which has to be a slice of `input` for the location to be known.
+    pub fn at(input: &str, at: &str, message: impl Into<String>) -> AocError {
+        let (line, column) = locate(input, at).unzip();
+
+        AocError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {line}, column {column}: ")?,
+            (Some(line), None) => write!(f, "line {line}: ")?,
+            _ => (),
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AocError {}
+
+/// Finds the 1-based line and column of `at` in `input`, `None` if `at` isn't a slice of `input`.
+pub fn locate(input: &str, at: &str) -> Option<(usize, usize)> {
+    let start = input.as_ptr() as usize;
+    let offset = (at.as_ptr() as usize).checked_sub(start)?;
+
+    if offset > input.len() {
+        return None;
+    }
+
+    let before = &input.as_bytes()[..offset];
+    let line = before.iter().filter(|&&c| c == b'\n').count() + 1;
+    let column = offset
+        - before
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1)
+        + 1;
+
+    Some((line, column))
+}
+
+/// Parses `s`, a slice of `input`, reporting where it is when it isn't a valid `T`.
+pub fn parse<T: FromStr>(input: &str, s: &str) -> Result<T> {
+    s.parse().map_err(|_| {
+        let name = type_name::<T>().rsplit("::").next().unwrap_or_default();
+        AocError::at(input, s, format!("expected {name}, found '{s}'"))
+    })
+}
+
+/// Attaches the location of `at` in `input` to a missing value or an error.
+pub trait Context<T> {
+    fn at(self, input: &str, at: &str, message: impl Display) -> Result<T>;
+}
+
+impl<T> Context<T> for Option<T> {
+    fn at(self, input: &str, at: &str, message: impl Display) -> Result<T> {
+        self.ok_or_else(|| AocError::at(input, at, message.to_string()))
+    }
+}
+
+impl<T, E> Context<T> for std::result::Result<T, E> {
+    fn at(self, input: &str, at: &str, message: impl Display) -> Result<T> {
+        self.map_err(|_| AocError::at(input, at, message.to_string()))
+    }
+}
+
+/// Strips `prefix` from `s`, a slice of `input`.
+pub fn strip_prefix<'a>(input: &str, s: &'a str, prefix: &str) -> Result<&'a str> {
+    s.strip_prefix(prefix)
+        .at(input, s, format!("expected '{prefix}'"))
+}
+
+/// Splits `s`, a slice of `input`, at the first `delimiter`.
+pub fn split_once<'a>(input: &str, s: &'a str, delimiter: &str) -> Result<(&'a str, &'a str)> {
+    s.split_once(delimiter).at(
+        input,
+        s,
+        match delimiter {
+            "\n\n" => "expected a blank line".to_string(),
+            _ => format!("expected '{}'", delimiter.escape_debug()),
+        },
+    )
+}
+
+/// The empty slice at the end of `input`, for reporting that something is missing.
+pub fn end(input: &str) -> &str {
+    &input[input.len()..]
+}
+
+#[test]
+fn test_locate() {
+    let input = "ab\ncd\n\nef";
+
+    assert_eq!(locate(input, &input[0..]), Some((1, 1)));
+    assert_eq!(locate(input, &input[4..]), Some((2, 2)));
+    assert_eq!(locate(input, &input[8..]), Some((4, 2)));
+    assert_eq!(locate(input, end(input)), Some((4, 3)));
+    assert_eq!(locate(input, &String::from("cd")), None);
+}
+
+#[test]
+fn test_errors() {
+    let input = "p=1,2\np=3,x";
+    let x = &input[10..];
+
+    assert_eq!(
+        parse::<i32>(input, x).unwrap_err().to_string(),
+        "line 2, column 5: expected i32, found 'x'"
+    );
+    assert_eq!(
+        strip_prefix(input, &input[6..], "v=")
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 1: expected 'v='"
+    );
+    assert_eq!(AocError::new("no path").to_string(), "no path");
+}
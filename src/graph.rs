@@ -0,0 +1,353 @@
+use crate::AdventHashMap;
+use bit_vec::BitVec;
+use std::{cell::Cell, hash::Hash};
+
+/// An undirected graph with its nodes interned to ids `0..len()`, in the order they first
+/// appeared.
+///
+/// Each node's neighbours are kept both as a sorted list and as a bitset, the bitsets make the
+/// set operations of Bron–Kerbosch cheap.
+#[derive(Clone)]
+pub struct Graph<N> {
+    names: Vec<N>,
+    ids: AdventHashMap<N, usize>,
+    neighbours: Vec<Vec<usize>>,
+    adjacency: Vec<BitVec>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+/// The ids of the set bits.
+fn ones(bits: &BitVec) -> impl Iterator<Item = usize> + '_ {
+    bits.blocks().enumerate().flat_map(|(i, mut block)| {
+        std::iter::from_fn(move || {
+            (block != 0).then(|| {
+                let bit = block.trailing_zeros() as usize;
+                block &= block - 1;
+                i * 32 + bit
+            })
+        })
+    })
+}
+
+fn intersection(a: &BitVec, b: &BitVec) -> BitVec {
+    let mut res = a.clone();
+    res.and(b);
+    res
+}
+
+impl<N: Copy + Eq + Hash> Graph<N> {
+    /// Builds the graph from its edges, duplicates are ignored. An edge from a node to itself
+    /// only adds the node, as a node being its own neighbour breaks the clique search.
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Graph<N> {
+        let mut names = Vec::new();
+        let mut ids = AdventHashMap::default();
+        let mut neighbours = Vec::<Vec<usize>>::new();
+
+        let mut intern = |name: N, neighbours: &mut Vec<Vec<usize>>| {
+            *ids.entry(name).or_insert_with(|| {
+                names.push(name);
+                neighbours.push(Vec::new());
+                names.len() - 1
+            })
+        };
+
+        for (a, b) in edges {
+            let a = intern(a, &mut neighbours);
+            let b = intern(b, &mut neighbours);
+
+            if a == b {
+                continue;
+            }
+
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+
+        for n in &mut neighbours {
+            n.sort_unstable();
+            n.dedup();
+        }
+
+        let adjacency = neighbours
+            .iter()
+            .map(|n| {
+                let mut bits = BitVec::from_elem(names.len(), false);
+                for &i in n {
+                    bits.set(i, true);
+                }
+                bits
+            })
+            .collect();
+
+        Graph {
+            names,
+            ids,
+            neighbours,
+            adjacency,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: N) -> Option<usize> {
+        self.ids.get(&name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> N {
+        self.names[id]
+    }
+
+    pub fn names(&self, ids: &[usize]) -> Vec<N> {
+        ids.iter().map(|&id| self.names[id]).collect()
+    }
+
+    /// The neighbours of `id`, sorted.
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.neighbours[id]
+    }
+
+    pub fn is_adjacent(&self, a: usize, b: usize) -> bool {
+        self.adjacency[a][b]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.neighbours.iter().map(|n| n.len()).sum::<usize>() / 2
+    }
+
+    /// Every set of three nodes connected to each other, each once with its ids ascending.
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut triangles = Vec::new();
+
+        // The neighbours of `id` with a higher id.
+        let after = |id: usize| {
+            let n = &self.neighbours[id];
+            &n[n.partition_point(|&n| n <= id)..]
+        };
+
+        for a in 0..self.len() {
+            for &b in after(a) {
+                for &c in after(b) {
+                    if self.is_adjacent(a, c) {
+                        triangles.push([a, b, c]);
+                    }
+                }
+            }
+        }
+
+        triangles
+    }
+
+    /// Pivoted Bron–Kerbosch, `found` is called with every maximal clique extending `r` with
+    /// nodes from `p` and none from `x`. `prune` gets the size of `r` and of `p` before each
+    /// branch and skips it by returning false.
+    fn bron_kerbosch(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: BitVec,
+        mut x: BitVec,
+        prune: &mut impl FnMut(usize, usize) -> bool,
+        found: &mut impl FnMut(&[usize]),
+    ) {
+        if p.none() {
+            if x.none() {
+                found(r);
+            }
+            return;
+        }
+
+        if !prune(r.len(), p.count_ones() as usize) {
+            return;
+        }
+
+        // Only the candidates not adjacent to the pivot need to be tried, any maximal clique
+        // contains either the pivot or one of them.
+        let pivot = ones(&p)
+            .chain(ones(&x))
+            .max_by_key(|&u| intersection(&p, &self.adjacency[u]).count_ones())
+            .unwrap();
+
+        let mut candidates = p.clone();
+        candidates.difference(&self.adjacency[pivot]);
+
+        for v in ones(&candidates) {
+            r.push(v);
+            self.bron_kerbosch(
+                r,
+                intersection(&p, &self.adjacency[v]),
+                intersection(&x, &self.adjacency[v]),
+                prune,
+                found,
+            );
+            r.pop();
+
+            p.set(v, false);
+            x.set(v, true);
+        }
+    }
+
+    /// Every clique that can't be extended with another node, with their ids ascending.
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let mut cliques = Vec::new();
+
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            BitVec::from_elem(self.len(), true),
+            BitVec::from_elem(self.len(), false),
+            &mut |_, _| true,
+            &mut |clique| {
+                let mut clique = clique.to_vec();
+                clique.sort_unstable();
+                cliques.push(clique);
+            },
+        );
+
+        cliques
+    }
+
+    /// A largest clique, with its ids ascending.
+    pub fn max_clique(&self) -> Vec<usize> {
+        let mut max_clique = Vec::<usize>::new();
+        let max_len = Cell::new(0);
+
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            BitVec::from_elem(self.len(), true),
+            BitVec::from_elem(self.len(), false),
+            &mut |r, p| r + p > max_len.get(),
+            &mut |clique| {
+                if clique.len() > max_len.get() {
+                    max_len.set(clique.len());
+                    max_clique = clique.to_vec();
+                }
+            },
+        );
+
+        max_clique.sort_unstable();
+        max_clique
+    }
+
+    /// The connected components, each with its ids ascending, ordered by their lowest id.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+
+            seen[start] = true;
+
+            let mut component = Vec::new();
+            let mut stack = vec![start];
+
+            while let Some(id) = stack.pop() {
+                component.push(id);
+
+                for &n in &self.neighbours[id] {
+                    if !seen[n] {
+                        seen[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    pub fn degree_stats(&self) -> Option<DegreeStats> {
+        let degrees = self.neighbours.iter().map(|n| n.len());
+
+        Some(DegreeStats {
+            min: degrees.clone().min()?,
+            max: degrees.clone().max()?,
+            mean: degrees.sum::<usize>() as f64 / self.len() as f64,
+        })
+    }
+}
+
+#[test]
+fn test_graph() {
+    // Two triangles sharing the edge b-c, a four-clique b-c-d-e and a separate edge x-y.
+    let graph = Graph::from_edges([
+        ("a", "b"),
+        ("a", "c"),
+        ("b", "c"),
+        ("b", "d"),
+        ("b", "e"),
+        ("c", "d"),
+        ("c", "e"),
+        ("d", "e"),
+        ("x", "y"),
+        ("y", "x"),
+    ]);
+
+    assert_eq!(graph.len(), 7);
+    assert_eq!(graph.edge_count(), 9);
+    assert_eq!(
+        graph.names(graph.neighbours(graph.id("a").unwrap())),
+        ["b", "c"]
+    );
+    assert_eq!(graph.triangles().len(), 5);
+
+    let mut cliques = graph
+        .maximal_cliques()
+        .iter()
+        .map(|c| graph.names(c))
+        .collect::<Vec<_>>();
+    cliques.sort();
+
+    assert_eq!(
+        cliques,
+        [
+            vec!["a", "b", "c"],
+            vec!["b", "c", "d", "e"],
+            vec!["x", "y"]
+        ]
+    );
+    assert_eq!(graph.names(&graph.max_clique()), ["b", "c", "d", "e"]);
+
+    assert_eq!(
+        graph
+            .components()
+            .iter()
+            .map(|c| c.len())
+            .collect::<Vec<_>>(),
+        [5, 2]
+    );
+    assert_eq!(
+        graph.degree_stats(),
+        Some(DegreeStats {
+            min: 1,
+            max: 4,
+            mean: 18.0 / 7.0
+        })
+    );
+}
+
+#[test]
+fn test_self_loop() {
+    let graph = Graph::from_edges([("ab", "ab"), ("ab", "cd"), ("ef", "ef")]);
+
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.edge_count(), 1);
+    assert_eq!(graph.neighbours(graph.id("ab").unwrap()), [1]);
+    assert!(!graph.is_adjacent(0, 0));
+    assert_eq!(graph.names(&graph.max_clique()), ["ab", "cd"]);
+}
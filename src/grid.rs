@@ -0,0 +1,277 @@
+use crate::error::{AocError, Result};
+use glam::{ivec2, IVec2};
+use std::{
+    fmt::{self, Display},
+    iter::StepBy,
+    ops::{Index, IndexMut},
+    slice, str,
+};
+
+/// A 2D grid stored row by row.
+///
+/// [`get`](Grid::get) returns the grid's `outside` value for positions outside the grid and
+/// [`set`](Grid::set) ignores them, [`try_get`](Grid::try_get), [`get_mut`](Grid::get_mut) and
+/// indexing with `grid[pos]` are bounds-checked instead.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    pub data: Vec<T>,
+    pub width: i32,
+    pub height: i32,
+    /// What `get` returns outside the grid.
+    pub outside: T,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid filled with `initial`, which is also what it returns outside the grid.
+    pub fn new(width: i32, height: i32, initial: T) -> Grid<T> {
+        Grid {
+            data: vec![initial.clone(); (width * height) as usize],
+            width,
+            height,
+            outside: initial,
+        }
+    }
+
+    pub fn with_outside(mut self, outside: T) -> Grid<T> {
+        self.outside = outside;
+        self
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.data.fill(value);
+    }
+
+    /// Resizes the grid, keeping the cells that are still inside it where they were and filling
+    /// the new cells with `value`.
+    pub fn resize(&mut self, width: i32, height: i32, value: T) {
+        let mut data = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height {
+            for x in 0..width {
+                data.push(match self.index(ivec2(x, y)) {
+                    Some(i) => self.data[i].clone(),
+                    None => value.clone(),
+                });
+            }
+        }
+
+        self.data = data;
+        self.width = width;
+        self.height = height;
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a grid of bytes with one row per line, `b'.'` outside the grid.
+    pub fn parse(input: &str) -> Result<Grid<u8>> {
+        Grid::parse_with(input, b'.', Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid with one row per line, converting each byte with `f`. A byte `f` returns
+    /// `None` for is reported as an error at its position.
+    pub fn parse_with(input: &str, outside: T, f: impl Fn(u8) -> Option<T>) -> Result<Grid<T>> {
+        let mut data = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+
+        for line in input.lines() {
+            if *width.get_or_insert(line.len()) != line.len() {
+                return Err(AocError::at(
+                    input,
+                    line,
+                    format!("expected a row of width {}", width.unwrap()),
+                ));
+            }
+
+            for (i, c) in line.bytes().enumerate() {
+                data.push(f(c).ok_or_else(|| {
+                    AocError::at(
+                        input,
+                        &line[i..],
+                        format!("unexpected '{}'", line[i..].chars().next().unwrap()),
+                    )
+                })?);
+            }
+
+            height += 1;
+        }
+
+        // Only empty lines would make a grid of width 0, which can't be split into rows.
+        let width = width
+            .filter(|&width| width > 0)
+            .ok_or_else(|| AocError::new("empty grid"))?;
+
+        Ok(Grid {
+            data,
+            width: width as i32,
+            height,
+            outside,
+        })
+    }
+
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height
+    }
+
+    /// The index of `pos` in `data`, `None` outside the grid.
+    pub fn index(&self, pos: IVec2) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| (pos.x + pos.y * self.width) as usize)
+    }
+
+    /// The position of `index` in `data`.
+    pub fn pos(&self, index: usize) -> IVec2 {
+        ivec2(index as i32 % self.width, index as i32 / self.width)
+    }
+
+    pub fn get_ref(&self, pos: IVec2) -> Option<&T> {
+        self.index(pos).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut T> {
+        self.index(pos).map(|i| &mut self.data[i])
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| ivec2(x, y)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        self.positions().zip(&self.data)
+    }
+
+    pub fn row(&self, y: i32) -> &[T] {
+        let start = (y * self.width) as usize;
+        &self.data[start..start + self.width as usize]
+    }
+
+    pub fn rows(&self) -> slice::Chunks<'_, T> {
+        self.data.chunks(self.width as usize)
+    }
+
+    pub fn column(&self, x: i32) -> StepBy<slice::Iter<'_, T>> {
+        assert!(x >= 0 && x < self.width);
+        self.data[x as usize..].iter().step_by(self.width as usize)
+    }
+
+    /// The first position holding `needle`, row by row.
+    pub fn find_first(&self, needle: T) -> Option<IVec2>
+    where
+        T: PartialEq,
+    {
+        self.data
+            .iter()
+            .position(|v| *v == needle)
+            .map(|i| self.pos(i))
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    /// The cell at `pos`, the grid's `outside` value outside the grid.
+    pub fn get(&self, pos: IVec2) -> T {
+        self.try_get(pos).unwrap_or(self.outside)
+    }
+
+    pub fn try_get(&self, pos: IVec2) -> Option<T> {
+        self.index(pos).map(|i| self.data[i])
+    }
+
+    /// Sets the cell at `pos`, returns false and does nothing outside the grid.
+    pub fn set(&mut self, pos: IVec2, value: T) -> bool {
+        match self.index(pos) {
+            Some(i) => {
+                self.data[i] = value;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: IVec2) -> &T {
+        self.get_ref(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, pos: IVec2) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside the grid"))
+    }
+}
+
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_grid() {
+    let input = "#.#\n.S.\n";
+    let mut grid = Grid::parse(input).unwrap();
+
+    assert_eq!((grid.width, grid.height), (3, 2));
+    assert_eq!(grid.find_first(b'S'), Some(ivec2(1, 1)));
+    assert_eq!(grid.get(ivec2(2, 0)), b'#');
+    assert_eq!(grid.get(ivec2(-1, 0)), b'.');
+    assert_eq!(grid.try_get(ivec2(3, 0)), None);
+    assert_eq!(grid.to_string(), input);
+
+    assert!(grid.set(ivec2(0, 1), b'#'));
+    assert!(!grid.set(ivec2(0, 2), b'#'));
+    assert_eq!(grid[ivec2(0, 1)], b'#');
+
+    assert_eq!(grid.row(1), b"#S.");
+    assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), b"#.");
+    assert_eq!(
+        grid.iter()
+            .filter(|(_, c)| **c == b'#')
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>(),
+        [ivec2(0, 0), ivec2(2, 0), ivec2(0, 1)]
+    );
+
+    grid.resize(2, 3, b'~');
+    assert_eq!(grid.to_string(), "#.\n#S\n~~\n");
+}
+
+#[test]
+fn test_parse_with() {
+    let grid = Grid::parse_with("01\n23", 0, |c| c.is_ascii_digit().then(|| c - b'0')).unwrap();
+
+    assert_eq!(grid.data, [0, 1, 2, 3]);
+    assert_eq!(grid.outside, 0);
+    assert_eq!(
+        Grid::parse("..\n...").unwrap_err().to_string(),
+        "line 2, column 1: expected a row of width 2"
+    );
+    assert_eq!(
+        Grid::parse_with("01\n2x", 0, |c| c.is_ascii_digit().then(|| c - b'0'))
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 2: unexpected 'x'"
+    );
+    assert_eq!(Grid::parse("").unwrap_err().to_string(), "empty grid");
+    assert_eq!(Grid::parse("\n\n").unwrap_err().to_string(), "empty grid");
+}
+
+#[test]
+#[should_panic]
+fn test_index_outside() {
+    let grid = Grid::new(2, 2, 0);
+    let _ = grid[ivec2(2, 0)];
+}
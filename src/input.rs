@@ -0,0 +1,268 @@
+use crate::{params, registry::Day};
+use std::{
+    borrow::Cow,
+    env,
+    fmt::{self, Display},
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Used when neither `--input-dir` nor `AOC_INPUT_DIR` is set.
+pub const DEFAULT_DIR: &str = "inputs/{day}.txt";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A single file, only meaningful when running one day.
+    File(PathBuf),
+    Stdin,
+    /// A path pattern where `{day}` is replaced by the day number. A plain directory means `{dir}/{day}.txt`.
+    Dir(String),
+}
+
+impl Source {
+    pub fn from_env() -> Source {
+        Source::Dir(env::var("AOC_INPUT_DIR").unwrap_or_else(|_| DEFAULT_DIR.to_string()))
+    }
+
+    /// Parses an `--input` argument, where `-` means stdin.
+    pub fn from_arg(arg: &str) -> Source {
+        if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn is_single(&self) -> bool {
+        !matches!(self, Source::Dir(_))
+    }
+}
+
+pub struct Input {
+    /// File name of the input, `stdin` when read from stdin.
+    pub name: String,
+    pub text: Cow<'static, str>,
+    /// What [`normalize`] had to change in the text as it was read.
+    pub changes: Vec<Change>,
+    /// From the params file next to the input file, see [`params::load_for`].
+    pub params: Vec<(String, i64)>,
+}
+
+impl Input {
+    /// Identifies the input by its text, so inputs with the same file name but different
+    /// contents, like the compiled-in `5.txt` and someone's own `inputs/5.txt`, are told apart.
+    /// The 64 bit FNV-1a hash in hex.
+    pub fn id(&self) -> String {
+        let hash = self.text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+        format!("{hash:016x}")
+    }
+
+    fn new(name: String, text: Cow<'static, str>) -> Input {
+        let (text, changes) = normalize(text);
+        Input {
+            name,
+            text,
+            changes,
+            params: Vec::new(),
+        }
+    }
+}
+
+/// A difference from the form the solutions expect, see [`normalize`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Bom,
+    /// The number of lines ending in `\r\n`.
+    Crlf(usize),
+    /// The number of lines ending in spaces or tabs.
+    TrailingWhitespace(usize),
+    MissingFinalNewline,
+    /// The number of newlines after the one ending the last line.
+    ExtraFinalNewlines(usize),
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Bom => write!(f, "removed a UTF-8 byte order mark"),
+            Change::Crlf(n) => write!(f, "converted {n} CRLF line ending(s)"),
+            Change::TrailingWhitespace(n) => {
+                write!(f, "trimmed trailing whitespace from {n} line(s)")
+            }
+            Change::MissingFinalNewline => write!(f, "added a missing final newline"),
+            Change::ExtraFinalNewlines(n) => write!(f, "removed {n} extra newline(s) at the end"),
+        }
+    }
+}
+
+/// Converts `text` to what the solutions expect: no byte order mark, `\n` line endings, no
+/// whitespace at the end of lines and a single newline ending the last line. Returns the text
+/// unchanged when it already was, and what was changed.
+pub fn normalize(text: Cow<'static, str>) -> (Cow<'static, str>, Vec<Change>) {
+    let mut changes = Vec::new();
+
+    let mut rest = text.as_ref();
+    if let Some(without_bom) = rest.strip_prefix('\u{feff}') {
+        changes.push(Change::Bom);
+        rest = without_bom;
+    }
+
+    let mut lines = rest.split('\n').collect::<Vec<_>>();
+    let last = lines.len() - 1;
+    let mut crlf = 0;
+    let mut trailing_whitespace = 0;
+
+    for (i, line) in lines.iter_mut().enumerate() {
+        if i != last {
+            if let Some(without_cr) = line.strip_suffix('\r') {
+                crlf += 1;
+                *line = without_cr;
+            }
+        }
+
+        let trimmed = line.trim_end_matches([' ', '\t', '\r']);
+        if trimmed.len() != line.len() {
+            trailing_whitespace += 1;
+            *line = trimmed;
+        }
+    }
+
+    if crlf > 0 {
+        changes.push(Change::Crlf(crlf));
+    }
+    if trailing_whitespace > 0 {
+        changes.push(Change::TrailingWhitespace(trailing_whitespace));
+    }
+
+    let empty_at_end = lines.iter().rev().take_while(|l| l.is_empty()).count();
+    lines.truncate(lines.len() - empty_at_end);
+
+    match empty_at_end {
+        0 if !lines.is_empty() => changes.push(Change::MissingFinalNewline),
+        0 | 1 => (),
+        n => changes.push(Change::ExtraFinalNewlines(n - 1)),
+    }
+
+    if changes.is_empty() {
+        return (text, changes);
+    }
+
+    let mut normalized = lines.join("\n");
+    if !lines.is_empty() {
+        normalized.push('\n');
+    }
+
+    (Cow::Owned(normalized), changes)
+}
+
+pub fn day_path(pattern: &str, day: u32) -> PathBuf {
+    if pattern.contains("{day}") {
+        PathBuf::from(pattern.replace("{day}", &day.to_string()))
+    } else {
+        Path::new(pattern).join(format!("{day}.txt"))
+    }
+}
+
+fn read_file(path: &Path) -> Result<Input, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+
+    let mut input = Input::new(
+        path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        Cow::Owned(text),
+    );
+    input.params = params::load_for(path)?;
+
+    Ok(input)
+}
+
+/// Loads the input for `day`, falling back to the compiled-in input when the file is missing.
+pub fn load(day: &Day, source: &Source) -> Result<Input, String> {
+    match source {
+        Source::File(path) => read_file(path),
+        Source::Stdin => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Could not read stdin: {e}"))?;
+
+            Ok(Input::new("stdin".to_string(), Cow::Owned(text)))
+        }
+        Source::Dir(pattern) => {
+            let path = day_path(pattern, day.day);
+
+            if path.exists() {
+                read_file(&path)
+            } else if let Some(text) = day.input {
+                Ok(Input::new(format!("{}.txt", day.day), Cow::Borrowed(text)))
+            } else {
+                Err(format!(
+                    "No input for day {}, {} does not exist",
+                    day.day,
+                    path.display()
+                ))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_id() {
+    let input = |text| Input::new("5.txt".to_string(), Cow::Borrowed(text));
+
+    assert_eq!(input("").id(), "cbf29ce484222325");
+    assert_eq!(input("a\n").id(), input("a\r\n").id());
+    assert_ne!(input("a\n").id(), input("b\n").id());
+}
+
+#[test]
+fn test_day_path() {
+    assert_eq!(
+        day_path("inputs/{day}.txt", 5),
+        PathBuf::from("inputs/5.txt")
+    );
+    assert_eq!(day_path("inputs", 12), PathBuf::from("inputs/12.txt"));
+    assert_eq!(
+        day_path("alice/day{day}/input", 3),
+        PathBuf::from("alice/day3/input")
+    );
+}
+
+#[test]
+fn test_normalize() {
+    let normalize = |text: &'static str| normalize(Cow::Borrowed(text));
+
+    assert_eq!(normalize("ab\ncd\n"), (Cow::Borrowed("ab\ncd\n"), vec![]));
+    assert!(matches!(normalize("ab\n").0, Cow::Borrowed(_)));
+    assert_eq!(normalize(""), (Cow::Borrowed(""), vec![]));
+
+    assert_eq!(
+        normalize("\u{feff}ab\r\ncd \r\n\ne\t"),
+        (
+            Cow::Owned("ab\ncd\n\ne\n".to_string()),
+            vec![
+                Change::Bom,
+                Change::Crlf(2),
+                Change::TrailingWhitespace(2),
+                Change::MissingFinalNewline
+            ]
+        )
+    );
+    assert_eq!(
+        normalize("ab\n\n \n"),
+        (
+            Cow::Owned("ab\n".to_string()),
+            vec![Change::TrailingWhitespace(1), Change::ExtraFinalNewlines(2)]
+        )
+    );
+    assert_eq!(
+        Change::Crlf(3).to_string(),
+        "converted 3 CRLF line ending(s)"
+    );
+}
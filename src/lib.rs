@@ -1,8 +1,28 @@
-#![feature(stdarch_x86_avx512)]
-#![feature(avx512_target_feature)]
-#![feature(map_many_mut)]
+#![cfg_attr(feature = "nightly", allow(stable_features))]
+#![cfg_attr(feature = "nightly", feature(stdarch_x86_avx512))]
+#![cfg_attr(feature = "nightly", feature(avx512_target_feature))]
 
+pub mod answers;
+pub mod baseline;
+pub mod bitgrid;
+pub mod cycle;
 pub mod days;
+pub mod dir;
+pub mod error;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod memory;
+pub mod output;
+pub mod parallel;
+pub mod params;
+pub mod parse;
+pub mod registry;
+pub mod render;
+pub mod scaffold;
+pub mod search;
+pub mod span;
+pub mod timing;
 pub use days::*;
 
 pub type AdventHashMap<K, V> = rustc_hash::FxHashMap<K, V>;
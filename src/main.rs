@@ -1,100 +1,638 @@
-use std::{any::type_name, fmt::Display, time::Instant};
+use aoc2024::{
+    answers::{self, Answers, Verdict},
+    baseline::{self, Baseline},
+    day14_simd::{self, Kernel},
+    error::AocError,
+    input::{self, Input, Source},
+    memory::{self, CountingAlloc},
+    parallel::{self, Job},
+    params::{self, Params},
+    registry::{self, Day, Part, Solver},
+    render::{FrameWriter, ImageFormat, Sink},
+    scaffold, span,
+    timing::{self, Format, Timing},
+};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Instant,
+};
 
-use aoc2024::*;
-use glam::ivec2;
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
 
-fn time<F, N>(f: F, input: &str)
-where
-    F: FnOnce(&str) -> N,
-    N: Display,
-{
+const USAGE: &str = "\
+Usage: aoc2024 run <days> [options]
+       aoc2024 check [<days>] [options]
+       aoc2024 time [<days>] [options]
+       aoc2024 render <day> [options]
+       aoc2024 params [<days>]
+       aoc2024 new <day>
+
+`run` prints the answers and times, `check` compares the answers with the
+answers file and fails on any mismatch, `time` runs each part repeatedly and
+reports min/median/mean/p95 times. `render` draws the solution of a day in
+colour in the terminal, or every frame of it as images with --frames.
+`params` lists the parameters of the days and their defaults. `new` creates
+src/days/dayNN.rs from the src/day.rs template, registers it and creates empty
+input files.
+
+<days> is one of:
+  all       every finished part of every day
+  5         both parts of day 5
+  14b       only part b of day 14
+  3..=12    days 3 to 12, `3..12` excludes day 12
+and can be combined with commas, e.g. `1,3..=5,14b`.
+
+Options:
+  -i, --input <file>   read the input from <file>, `-` reads stdin
+  --input-dir <dir>    read inputs from <dir>/{day}.txt or from a pattern
+                       like `alice/day{day}.txt`, defaults to $AOC_INPUT_DIR
+                       or `inputs/{day}.txt`
+  --param <name=value> set a parameter of the selected days that have it, like
+                       `--param width=11` for day 14, can be repeated
+  --answers <file>     answers file for `check`, defaults to $AOC_ANSWERS or
+                       `answers.txt`
+  --record             add answers that aren't in the answers file yet
+  --warmup <n>         unmeasured runs before timing, defaults to 3
+  --runs <n>           measured runs, defaults to 10
+  --format <format>    `table`, `json` or `csv`, defaults to `table`
+  --baseline <file>    baseline file for `time`, defaults to $AOC_BASELINE or
+                       `baseline.txt`
+  --save-baseline      save the median times of `time` to the baseline file
+  --compare            compare the median times of `time` with the baseline
+                       and fail if a part got slower than the threshold
+  --threshold <n>      percent a part may get slower before it counts as a
+                       regression, defaults to 10
+  --parallel           `run` the days and parts concurrently and report the
+                       per-day, summed CPU and wall-clock times
+  --threads <n>        threads for --parallel, defaults to one per core
+  --alloc              count the allocations of each part, `time` counts
+                       them in one extra run, can't be used with --parallel
+  --spans              time the phases the solutions mark, like parse and
+                       search, `time` times them in one extra run, needs the
+                       `spans` feature and can't be used with --parallel
+  --frames <dir>       `render` to numbered images in <dir> instead
+  --image <format>     `ppm` or `pgm` images for --frames, defaults to `ppm`
+  --scale <n>          pixels per cell for --frames, defaults to 4
+  --kernel <kernel>    force the `avx512`, `avx2`, `sse4.1` or `scalar`
+                       version of day 14 part b instead of the fastest one
+                       the CPU supports, `avx512` needs the `nightly` feature
+
+Days without an input file use the compiled-in input when built with the
+`embedded-inputs` feature. Inputs are read with `\\n` line endings, without a
+byte order mark or trailing whitespace and ending in a single newline, any
+changes made to get there are printed to stderr.
+
+Parameters default to the values for the real inputs. An input file can come
+with a params file next to it that sets them for that input, with the same
+name but a `.params` extension, e.g. `14_test.params` for `14_test.txt`,
+containing lines like `width = 11`. --param overrides both.";
+
+struct Options {
+    source: Source,
+    params: Vec<(String, i64)>,
+    answers: PathBuf,
+    record: bool,
+    warmup: usize,
+    runs: usize,
+    format: Format,
+    baseline: PathBuf,
+    save_baseline: bool,
+    compare: bool,
+    threshold: f64,
+    parallel: bool,
+    threads: usize,
+    alloc: bool,
+    spans: bool,
+    frames: Option<PathBuf>,
+    image: ImageFormat,
+    scale: u32,
+}
+
+/// Runs and prints one part, returns false if it failed.
+fn time(name: &str, f: Solver, input: &str, params: &Params, options: &Options) -> bool {
     let start = Instant::now();
-    let answer = f(input);
+    let ((answer, spans), stats) = if options.alloc {
+        let (res, stats) = memory::measure(|| span::collect(|| f(input, params)));
+        (res, Some(stats))
+    } else {
+        (span::collect(|| f(input, params)), None)
+    };
     let elapsed = start.elapsed();
 
-    println!(
-        "{} Time {} us: {answer}",
-        type_name::<F>().trim_start_matches("aoc2024::days::"),
-        elapsed.as_micros()
-    );
+    match answer {
+        Ok(answer) => {
+            println!("{name} Time {} us: {answer}", elapsed.as_micros());
+
+            if let Some(stats) = stats {
+                println!(
+                    "{name} Memory {} allocs, {} allocated, {} peak",
+                    stats.allocs,
+                    memory::format_bytes(stats.bytes),
+                    memory::format_bytes(stats.peak)
+                );
+            }
+
+            if options.spans {
+                for s in spans {
+                    println!(
+                        "{name} Phase {} {} us ({} call(s))",
+                        s.name,
+                        s.total.as_micros(),
+                        s.calls
+                    );
+                }
+            }
+
+            true
+        }
+        Err(err) => {
+            eprintln!("{name} {err}");
+            false
+        }
+    }
 }
 
-fn main() {
-    let start = Instant::now();
+fn failures(failed: usize) -> Result<(), String> {
+    if failed > 0 {
+        Err(format!("{failed} part(s) failed"))
+    } else {
+        Ok(())
+    }
+}
 
-    /*time(day01::a, day01::INPUT);
-    time(day01::b, day01::INPUT);
+/// Loads the inputs of the selected days with their params.
+fn load_inputs(
+    selected: &[(&'static Day, Part)],
+    options: &Options,
+) -> Result<Vec<(u32, Input, Params)>, String> {
+    if options.source.is_single() && selected.iter().any(|(d, _)| d.day != selected[0].0.day) {
+        return Err("--input can only be used with a single day".to_string());
+    }
 
-    time(day02::a, day02::INPUT);
-    time(day02::b, day02::INPUT);
+    for (name, _) in &options.params {
+        if !selected
+            .iter()
+            .any(|(d, _)| d.params.iter().any(|p| p.name == name))
+        {
+            return Err(format!(
+                "None of the selected days has a parameter '{name}'"
+            ));
+        }
+    }
 
-    time(day03::a, day03::INPUT);
-    time(day03::b, day03::INPUT);
+    let mut inputs = Vec::<(u32, Input, Params)>::new();
 
-    time(day04::a, day04::INPUT);
-    time(day04::b, day04::INPUT);
+    for (day, _) in selected {
+        if !inputs.iter().any(|(d, _, _)| *d == day.day) {
+            let input = input::load(day, &options.source)?;
+            let params = params::resolve(day, &input, &options.params)?;
 
-    time(day05::a, day05::INPUT);
-    time(day05::b, day05::INPUT);
+            for change in &input.changes {
+                eprintln!("{}: {change}", input.name);
+            }
 
-    time(day06::a, day06::INPUT);
-    time(day06::b, day06::INPUT);
+            if params != Params::defaults(day.params) {
+                eprintln!("{}: {params}", input.name);
+            }
 
-    time(day07::a, day07::INPUT);
-    time(day07::b, day07::INPUT);
+            inputs.push((day.day, input, params));
+        }
+    }
 
-    time(day08::a, day08::INPUT);
-    time(day08::b, day08::INPUT);
+    Ok(inputs)
+}
 
-    time(day09::a, day09::INPUT);
-    time(day09::b, day09::INPUT);
+fn find_input(inputs: &[(u32, Input, Params)], day: u32) -> (&Input, &Params) {
+    let (_, input, params) = inputs.iter().find(|(d, _, _)| *d == day).unwrap();
+    (input, params)
+}
+
+fn run_parallel(
+    selected: &[(&'static Day, Part)],
+    inputs: &[(u32, Input, Params)],
+    options: &Options,
+) -> Result<(), String> {
+    let jobs = selected
+        .iter()
+        .map(|&(day, part)| {
+            let (input, params) = find_input(inputs, day.day);
 
-    time(day10::a, day10::INPUT);
-    time(day10::b, day10::INPUT);
+            Job {
+                day,
+                part,
+                input: &input.text,
+                params,
+            }
+        })
+        .collect::<Vec<_>>();
 
-    time(day11::a, day11::INPUT);
-    time(day11::b, day11::INPUT);
+    let report = parallel::run(&jobs, options.threads)?;
+    let mut failed = 0;
 
-    time(day12::a, day12::INPUT);
-    time(day12::b, day12::INPUT);
+    for f in &report.finished {
+        let name = format!("{}::{}", f.day.name, f.part);
 
-    time(day13::a, day13::INPUT);
-    time(day13::b, day13::INPUT);
+        print!("{}", f.output);
 
-    time(|input| day14::a(input, ivec2(101, 103)), day14::INPUT);
-    time(|input| day14_simd::b(input, ivec2(101, 103)), day14::INPUT);
+        match &f.answer {
+            Ok(answer) => println!("{name} Time {} us: {answer}", f.time.as_micros()),
+            Err(err) => {
+                eprintln!("{name} {err}");
+                failed += 1;
+            }
+        }
+    }
 
-    time(day15::a, day15::INPUT);
-    time(day15::b, day15::INPUT);
+    println!();
 
-    time(day16::a, day16::INPUT);
-    time(day16::b, day16::INPUT);
+    for (day, time) in report.day_times() {
+        println!("{} Time {} us", day.name, time.as_micros());
+    }
 
-    time(day17::a, day17::INPUT);
-    time(day17::b, day17::INPUT);
+    println!();
+    println!("CPU Time {} us", report.cpu_time().as_micros());
+    println!("Wall-clock Time {} us", report.wall_time.as_micros());
+
+    failures(failed)
+}
 
-    time(|input| day18::a(input, ivec2(71, 71), 1024), day18::INPUT);
-    time(|input| day18::b(input, ivec2(71, 71)), day18::INPUT);
+fn run(spec: &str, options: &Options) -> Result<(), String> {
+    let selected = registry::select(spec)?;
+    let inputs = load_inputs(&selected, options)?;
 
-    time(day19::a, day19::INPUT);
-    time(day19::b, day19::INPUT);
+    if options.parallel {
+        if options.alloc {
+            return Err("--alloc can't be used with --parallel".to_string());
+        }
 
-    time(|input| day20::a(input, 100), day20::INPUT);
-    time(|input| day20::b(input, 100), day20::INPUT);
+        if options.spans {
+            return Err("--spans can't be used with --parallel".to_string());
+        }
 
-    //time(day21::a, day21::INPUT);
-    //time(day21::b, day21::INPUT);
+        return run_parallel(&selected, &inputs, options);
+    }
 
-    time(day22::a, day22::INPUT);
-    time(day22::b, day22::INPUT);
+    let start = Instant::now();
+    let mut failed = 0;
 
-    time(day23::a, day23::INPUT);
-    time(day23::b, day23::INPUT);
+    for (day, part) in selected {
+        let (input, params) = find_input(&inputs, day.day);
 
-    time(day24::a, day24::INPUT);*/
-    time(day24::b, day24::INPUT);
+        if !time(
+            &format!("{}::{part}", day.name),
+            day.solver(part),
+            &input.text,
+            params,
+            options,
+        ) {
+            failed += 1;
+        }
+    }
 
     let elapsed = start.elapsed();
 
     println!("Total Time {} us", elapsed.as_micros());
+
+    failures(failed)
+}
+
+fn check(spec: &str, options: &Options) -> Result<(), String> {
+    let selected = registry::select(spec)?;
+    let inputs = load_inputs(&selected, options)?;
+    let answers = Answers::load(&options.answers)?;
+
+    let mut failed = 0;
+
+    for (day, part) in selected {
+        let (input, params) = find_input(&inputs, day.day);
+        let name = format!("{}::{part} {}", day.name, input.name);
+
+        let answer = match day.solver(part)(&input.text, params) {
+            Ok(answer) => answer,
+            Err(err) => {
+                println!("{name} ERROR: {err}");
+                failed += 1;
+                continue;
+            }
+        };
+
+        match answers.check(day.day, part, &input.id(), &answer) {
+            Verdict::Pass => println!("{name} pass: {answer}"),
+            Verdict::Fail { expected } => {
+                println!("{name} FAIL: {answer}, expected {expected}");
+                failed += 1;
+            }
+            Verdict::Unknown if options.record => {
+                Answers::append(
+                    &options.answers,
+                    day.day,
+                    part,
+                    &input.id(),
+                    &input.name,
+                    &answer,
+                )?;
+                println!("{name} recorded: {answer}");
+            }
+            Verdict::Unknown => println!("{name} unknown: {answer}"),
+        }
+    }
+
+    failures(failed)
+}
+
+fn time_all(spec: &str, options: &Options) -> Result<(), String> {
+    let selected = registry::select(spec)?;
+    let inputs = load_inputs(&selected, options)?;
+
+    let mut timings = Vec::new();
+    let mut failed = 0;
+
+    for (day, part) in selected {
+        let (input, params) = find_input(&inputs, day.day);
+        let name = format!("{}::{part}", day.name);
+
+        let (answer, stats) = match timing::measure(
+            day.solver(part),
+            &input.text,
+            params,
+            options.warmup,
+            options.runs,
+        ) {
+            Ok(res) => res,
+            Err(err) => {
+                eprintln!("{name} {err}");
+                failed += 1;
+                continue;
+            }
+        };
+
+        let alloc = options
+            .alloc
+            .then(|| memory::measure(|| day.solver(part)(&input.text, params)).1);
+
+        let spans = if options.spans {
+            span::collect(|| day.solver(part)(&input.text, params)).1
+        } else {
+            Vec::new()
+        };
+
+        timings.push(Timing {
+            day: day.day,
+            part,
+            name,
+            input: input.name.clone(),
+            answer,
+            stats,
+            alloc,
+            spans,
+        });
+    }
+
+    print!("{}", timing::report(&timings, options.format));
+
+    let mut regressions = 0;
+
+    if options.compare {
+        let baseline = Baseline::load(&options.baseline)?;
+
+        if baseline.is_empty() {
+            return Err(format!(
+                "No baseline in {}, save one with --save-baseline",
+                options.baseline.display()
+            ));
+        }
+
+        let comparisons = baseline.compare(&timings, options.threshold);
+        regressions = comparisons
+            .iter()
+            .filter(|c| c.verdict == baseline::Verdict::Regressed)
+            .count();
+
+        // Keep the json and csv output parseable.
+        if options.format == Format::Table {
+            print!("\n{}", baseline::report(&comparisons));
+        } else {
+            eprint!("{}", baseline::report(&comparisons));
+        }
+    }
+
+    if options.save_baseline {
+        Baseline::save(&options.baseline, &timings)?;
+        eprintln!("Saved baseline to {}", options.baseline.display());
+    }
+
+    if regressions > 0 {
+        return Err(format!(
+            "{regressions} part(s) more than {}% slower than the baseline",
+            options.threshold
+        ));
+    }
+
+    failures(failed)
+}
+
+fn render(day: &str, options: &Options) -> Result<(), String> {
+    let day = day
+        .parse()
+        .ok()
+        .and_then(registry::get)
+        .ok_or_else(|| format!("Invalid day '{day}'"))?;
+    let render = day
+        .render
+        .ok_or_else(|| format!("{} can't be rendered", day.name))?;
+
+    let inputs = load_inputs(&[(day, Part::A)], options)?;
+    let (input, params) = find_input(&inputs, day.day);
+    let run = |sink: &mut Sink| {
+        render(&input.text, params, sink).map_err(|err| format!("{} {err}", day.name))
+    };
+
+    match &options.frames {
+        Some(dir) => {
+            let mut writer = FrameWriter::new(dir, options.image, options.scale)
+                .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+            let mut count = 0;
+
+            run(&mut |frame| {
+                count += 1;
+                writer
+                    .write(&frame)
+                    .map(|_| ())
+                    .map_err(|err| AocError::new(format!("failed to write a frame: {err}")))
+            })?;
+
+            println!("Wrote {count} frame(s) to {}", dir.display());
+        }
+        None => {
+            // Only the last frame is shown, so the others are dropped as they come.
+            let mut last = None;
+
+            run(&mut |frame| {
+                last = Some(frame);
+                Ok(())
+            })?;
+
+            if let Some(frame) = last {
+                frame
+                    .write_terminal(&mut std::io::stdout().lock())
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn list_params(spec: &str) -> Result<(), String> {
+    let mut selected = registry::select(spec)?;
+    selected.dedup_by_key(|(day, _)| day.day);
+
+    for (day, _) in selected {
+        for param in day.params {
+            println!(
+                "{} {} = {}  {}..={}  {}",
+                day.name,
+                param.name,
+                param.default,
+                param.range.start(),
+                param.range.end(),
+                param.help
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn new_day(day: &str) -> Result<(), String> {
+    let day = day.parse().map_err(|_| format!("Invalid day '{day}'"))?;
+
+    for path in scaffold::scaffold(Path::new(env!("CARGO_MANIFEST_DIR")), day)? {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn parse_count(arg: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{value}' for {arg}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(Vec<String>, Options), String> {
+    let mut positional = Vec::new();
+    let mut options = Options {
+        source: Source::from_env(),
+        params: Vec::new(),
+        answers: PathBuf::from(
+            env::var("AOC_ANSWERS").unwrap_or_else(|_| answers::DEFAULT_PATH.to_string()),
+        ),
+        record: false,
+        warmup: 3,
+        runs: 10,
+        format: Format::Table,
+        baseline: PathBuf::from(
+            env::var("AOC_BASELINE").unwrap_or_else(|_| baseline::DEFAULT_PATH.to_string()),
+        ),
+        save_baseline: false,
+        compare: false,
+        threshold: 10.0,
+        parallel: false,
+        threads: 0,
+        alloc: false,
+        spans: false,
+        frames: None,
+        image: ImageFormat::Ppm,
+        scale: 4,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+
+        match arg.as_str() {
+            "-i" | "--input" => options.source = Source::from_arg(&value()?),
+            "--input-dir" => options.source = Source::Dir(value()?),
+            "--param" => options
+                .params
+                .push(params::parse_assignment(&value()?).map_err(|err| format!("{arg}: {err}"))?),
+            "--answers" => options.answers = PathBuf::from(value()?),
+            "--record" => options.record = true,
+            "--warmup" => options.warmup = parse_count(&arg, &value()?)?,
+            "--runs" => options.runs = parse_count(&arg, &value()?)?.max(1),
+            "--format" => options.format = Format::from_arg(&value()?)?,
+            "--baseline" => options.baseline = PathBuf::from(value()?),
+            "--save-baseline" => options.save_baseline = true,
+            "--compare" => options.compare = true,
+            "--threshold" => {
+                let value = value()?;
+                options.threshold = value
+                    .parse()
+                    .ok()
+                    .filter(|t: &f64| *t >= 0.0)
+                    .ok_or_else(|| format!("Invalid value '{value}' for {arg}"))?;
+            }
+            "--parallel" => options.parallel = true,
+            "--threads" => options.threads = parse_count(&arg, &value()?)?,
+            "--alloc" => options.alloc = true,
+            "--spans" if !span::ENABLED => return Err(
+                "--spans needs the `spans` feature, e.g. `cargo run --release --features spans`"
+                    .to_string(),
+            ),
+            "--spans" => options.spans = true,
+            "--frames" => options.frames = Some(PathBuf::from(value()?)),
+            "--image" => {
+                options.image = match value()?.as_str() {
+                    "ppm" => ImageFormat::Ppm,
+                    "pgm" => ImageFormat::Pgm,
+                    other => return Err(format!("Invalid value '{other}' for {arg}")),
+                }
+            }
+            "--kernel" => day14_simd::force(Some(Kernel::from_arg(&value()?)?))?,
+            "--scale" => options.scale = parse_count(&arg, &value()?)?.max(1) as u32,
+            "-h" | "--help" => positional.push("help".to_string()),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}\n\n{USAGE}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    Ok((positional, options))
+}
+
+fn main() -> ExitCode {
+    let res = parse_args(env::args().skip(1)).and_then(|(args, options)| {
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+        match args.as_slice() {
+            [] | ["run"] => run("all", &options),
+            ["run", spec] => run(spec, &options),
+            ["check"] => check("all", &options),
+            ["check", spec] => check(spec, &options),
+            ["time"] => time_all("all", &options),
+            ["time", spec] => time_all(spec, &options),
+            ["render", day] => render(day, &options),
+            ["params"] => list_params("all"),
+            ["params", spec] => list_params(spec),
+            ["new", day] => new_day(day),
+            ["help"] => {
+                println!("{USAGE}");
+                Ok(())
+            }
+            _ => Err(USAGE.to_string()),
+        }
+    });
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
 }
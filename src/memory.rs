@@ -0,0 +1,130 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+};
+
+/// The system allocator, counting allocations while [`measure`] runs.
+///
+/// Only counts when installed as the `#[global_allocator]`, which the runner does. The counters
+/// are global, so only one thing should be measured at a time.
+pub struct CountingAlloc;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn allocated(size: usize) {
+    ALLOCS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn freed(size: usize) {
+    // Saturating, memory allocated before counting started can be freed during it.
+    let _ = LIVE.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |live| {
+        Some(live.saturating_sub(size))
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+
+        if ENABLED.load(Ordering::Relaxed) {
+            freed(layout.size());
+        }
+    }
+
+    /// Counted as freeing the old block and allocating the new one.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+
+        if !new_ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            freed(layout.size());
+            allocated(new_size);
+        }
+
+        new_ptr
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct AllocStats {
+    /// Allocations, including reallocations.
+    pub allocs: u64,
+    /// Bytes allocated in total.
+    pub bytes: u64,
+    /// The most bytes allocated at once.
+    pub peak: u64,
+}
+
+/// Runs `f` counting its allocations. Everything is 0 unless [`CountingAlloc`] is the global
+/// allocator.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    ALLOCS.store(0, Ordering::Relaxed);
+    BYTES.store(0, Ordering::Relaxed);
+    LIVE.store(0, Ordering::Relaxed);
+    PEAK.store(0, Ordering::Relaxed);
+
+    ENABLED.store(true, Ordering::SeqCst);
+    let res = f();
+    ENABLED.store(false, Ordering::SeqCst);
+
+    let stats = AllocStats {
+        allocs: ALLOCS.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+        peak: PEAK.load(Ordering::Relaxed) as u64,
+    };
+
+    (res, stats)
+}
+
+/// `bytes` in B, KiB, MiB or GiB with one decimal.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!(format_bytes(0), "0 B");
+    assert_eq!(format_bytes(1023), "1023 B");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(3 << 30), "3.0 GiB");
+}
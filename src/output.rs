@@ -0,0 +1,51 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Write},
+};
+
+thread_local! {
+    static CAPTURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Prints a line from a solution, use [`outln!`](crate::outln) instead of calling this.
+pub fn print_line(args: fmt::Arguments) {
+    CAPTURE.with_borrow_mut(|capture| match capture {
+        Some(out) => {
+            out.write_fmt(args).unwrap();
+            out.push('\n');
+        }
+        None => println!("{args}"),
+    });
+}
+
+/// Runs `f`, collecting what it prints with [`outln!`](crate::outln) on this thread instead of
+/// printing it.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, String) {
+    let previous = CAPTURE.replace(Some(String::new()));
+    let res = f();
+    let out = CAPTURE.replace(previous).unwrap_or_default();
+
+    (res, out)
+}
+
+/// `println!` for solutions, the parallel runner captures these per solution so the output of
+/// different days doesn't interleave.
+#[macro_export]
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        $crate::output::print_line(format_args!($($arg)*))
+    };
+}
+
+#[test]
+fn test_capture() {
+    let (res, out) = capture(|| {
+        outln!("a {}", 1);
+        let (_, inner) = capture(|| outln!("inner"));
+        outln!("b");
+        inner
+    });
+
+    assert_eq!(res, "inner\n");
+    assert_eq!(out, "a 1\nb\n");
+}
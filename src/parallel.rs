@@ -0,0 +1,101 @@
+use crate::{
+    error, output,
+    params::Params,
+    registry::{Day, Part},
+};
+use cpu_time::ThreadTime;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+pub struct Job<'a> {
+    pub day: &'static Day,
+    pub part: Part,
+    pub input: &'a str,
+    pub params: &'a Params,
+}
+
+pub struct Finished {
+    pub day: &'static Day,
+    pub part: Part,
+    pub answer: error::Result<String>,
+    /// What the solution printed with `outln!`.
+    pub output: String,
+    pub time: Duration,
+    pub cpu_time: Duration,
+}
+
+pub struct Report {
+    /// In the same order as the jobs.
+    pub finished: Vec<Finished>,
+    pub wall_time: Duration,
+}
+
+impl Report {
+    pub fn cpu_time(&self) -> Duration {
+        self.finished.iter().map(|f| f.cpu_time).sum()
+    }
+
+    /// The summed time of the parts of each day, in the order the days were first run.
+    pub fn day_times(&self) -> Vec<(&'static Day, Duration)> {
+        let mut days = Vec::<(&'static Day, Duration)>::new();
+
+        for f in &self.finished {
+            match days.iter_mut().find(|(d, _)| d.day == f.day.day) {
+                Some((_, time)) => *time += f.time,
+                None => days.push((f.day, f.time)),
+            }
+        }
+
+        days
+    }
+}
+
+fn run_job(job: &Job) -> Finished {
+    let cpu_start = ThreadTime::now();
+    let start = Instant::now();
+
+    let (answer, output) = output::capture(|| job.day.solver(job.part)(job.input, job.params));
+
+    Finished {
+        day: job.day,
+        part: job.part,
+        answer,
+        output,
+        time: start.elapsed(),
+        cpu_time: cpu_start.elapsed(),
+    }
+}
+
+/// Runs the jobs on a pool of `threads` threads, 0 uses one thread per core.
+pub fn run(jobs: &[Job], threads: usize) -> Result<Report, String> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| format!("Could not start the thread pool: {e}"))?;
+
+    let start = Instant::now();
+    let finished = pool.install(|| jobs.par_iter().with_max_len(1).map(run_job).collect());
+
+    Ok(Report {
+        finished,
+        wall_time: start.elapsed(),
+    })
+}
+
+#[test]
+fn test_run() {
+    let day = crate::registry::get(1).unwrap();
+    let params = Params::defaults(day.params);
+    let jobs = Part::ALL.map(|part| Job {
+        day,
+        part,
+        input: crate::day01::TEST_INPUT,
+        params: &params,
+    });
+
+    let report = run(&jobs, 2).unwrap();
+
+    assert_eq!(report.finished[0].answer, Ok("11".to_string()));
+    assert_eq!(report.finished[1].answer, Ok("31".to_string()));
+    assert_eq!(report.day_times().len(), 1);
+}
@@ -0,0 +1,226 @@
+use crate::{input::Input, registry::Day};
+use std::{
+    fmt::{self, Display},
+    fs,
+    ops::RangeInclusive,
+    path::Path,
+};
+
+/// A setting of a day that isn't part of its input, like the size of the grid in day 14.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub help: &'static str,
+    /// The value for the real puzzle inputs.
+    pub default: i64,
+    pub range: RangeInclusive<i64>,
+}
+
+/// The values of the parameters a day declares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Params {
+    declared: &'static [Param],
+    values: Vec<i64>,
+}
+
+impl Params {
+    pub fn defaults(declared: &'static [Param]) -> Params {
+        Params {
+            declared,
+            values: declared.iter().map(|p| p.default).collect(),
+        }
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.declared.iter().any(|p| p.name == name)
+    }
+
+    /// Panics if the day doesn't declare `name`.
+    pub fn get(&self, name: &str) -> i64 {
+        match self.declared.iter().position(|p| p.name == name) {
+            Some(i) => self.values[i],
+            None => panic!("undeclared parameter '{name}'"),
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) -> Result<(), String> {
+        let i = self
+            .declared
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| format!("unknown parameter '{name}'"))?;
+        let range = &self.declared[i].range;
+
+        if !range.contains(&value) {
+            return Err(format!(
+                "{name} must be in {}..={}, found {value}",
+                range.start(),
+                range.end()
+            ));
+        }
+
+        self.values[i] = value;
+        Ok(())
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (param, value)) in self.declared.iter().zip(&self.values).enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}={value}", param.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses `name=value`, as given to `--param` and in params files.
+pub fn parse_assignment(s: &str) -> Result<(String, i64), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected 'name=value', found '{s}'"))?;
+    let (name, value) = (name.trim(), value.trim());
+
+    let value = value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for {name}"))?;
+
+    Ok((name.to_string(), value))
+}
+
+/// Parses a params file, one `name = value` per line. Empty lines and lines starting with `#`
+/// are ignored.
+pub fn parse_file(text: &str) -> Result<Vec<(String, i64)>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(line_no, line)| (line_no, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_no, line)| {
+            parse_assignment(line).map_err(|err| format!("line {}: {err}", line_no + 1))
+        })
+        .collect()
+}
+
+/// Reads the params file next to an input file, `14_test.params` for `14_test.txt`. No file
+/// means no params.
+pub fn load_for(input: &Path) -> Result<Vec<(String, i64)>, String> {
+    let path = input.with_extension("params");
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let text =
+        fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+
+    parse_file(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// The params of `day` for `input`: the defaults, overridden by the input's params file and
+/// then by `overrides`. Overrides of parameters the day doesn't have are ignored, so the same
+/// overrides can be used for several days.
+pub fn resolve(day: &Day, input: &Input, overrides: &[(String, i64)]) -> Result<Params, String> {
+    let mut params = Params::defaults(day.params);
+
+    for (name, value) in &input.params {
+        params
+            .set(name, *value)
+            .map_err(|err| format!("{} params of {}: {err}", input.name, day.name))?;
+    }
+
+    for (name, value) in overrides {
+        if params.has(name) {
+            params
+                .set(name, *value)
+                .map_err(|err| format!("--param for {}: {err}", day.name))?;
+        }
+    }
+
+    Ok(params)
+}
+
+#[test]
+fn test_params() {
+    static DECLARED: &[Param] = &[
+        Param {
+            name: "width",
+            help: "",
+            default: 101,
+            range: 1..=1000,
+        },
+        Param {
+            name: "steps",
+            help: "",
+            default: 100,
+            range: 0..=1000,
+        },
+    ];
+
+    let mut params = Params::defaults(DECLARED);
+    assert_eq!(params.get("width"), 101);
+
+    params.set("width", 11).unwrap();
+    assert_eq!(params.to_string(), "width=11, steps=100");
+    assert_eq!(
+        params.set("width", 0).unwrap_err(),
+        "width must be in 1..=1000, found 0"
+    );
+    assert_eq!(
+        params.set("height", 7).unwrap_err(),
+        "unknown parameter 'height'"
+    );
+
+    assert_eq!(
+        parse_file("# example\nwidth = 11\n\nsteps=12\n").unwrap(),
+        [("width".to_string(), 11), ("steps".to_string(), 12)]
+    );
+    assert_eq!(
+        parse_file("width 11").unwrap_err(),
+        "line 1: expected 'name=value', found 'width 11'"
+    );
+    assert_eq!(
+        parse_assignment("steps=x").unwrap_err(),
+        "invalid value 'x' for steps"
+    );
+}
+
+#[test]
+fn test_resolve() {
+    use crate::{input::Source, registry::Part};
+
+    let day = crate::registry::get(18).unwrap();
+    let source = Source::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input/18_test.txt"));
+    let input = crate::input::load(day, &source).unwrap();
+
+    let params = resolve(day, &input, &[]).unwrap();
+    assert_eq!(params.to_string(), "size=7, steps=12");
+    assert_eq!(day.solver(Part::A)(&input.text, &params).unwrap(), "22");
+
+    let overrides = [("steps".to_string(), 20), ("width".to_string(), 5)];
+    let params = resolve(day, &input, &overrides).unwrap();
+    assert_eq!(params.get("steps"), 20);
+
+    let overrides = [("size".to_string(), 0)];
+    assert_eq!(
+        resolve(day, &input, &overrides).unwrap_err(),
+        "--param for day18: size must be in 1..=1000, found 0"
+    );
+
+    let day = crate::registry::get(14).unwrap();
+    let source = Source::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input/14_test.txt"));
+    let input = crate::input::load(day, &source).unwrap();
+    assert_eq!(
+        resolve(day, &input, &[]).unwrap().to_string(),
+        "width=11, height=7"
+    );
+
+    let overrides = [("width".to_string(), 200)];
+    assert_eq!(
+        resolve(day, &input, &overrides).unwrap_err(),
+        "--param for day14: width must be in 1..=127, found 200"
+    );
+}
@@ -0,0 +1,199 @@
+use crate::{
+    error::{self, AocError, Result},
+    grid::Grid,
+};
+use std::str::FromStr;
+
+/// The sections of `input` separated by blank lines, without their surrounding newlines.
+/// Empty sections are skipped.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|s| s.trim_matches('\n'))
+        .filter(|s| !s.is_empty())
+}
+
+/// Splits `input` at its first blank line, the second section without its trailing newlines.
+pub fn two_sections(input: &str) -> Result<(&str, &str)> {
+    let (first, second) = error::split_once(input, input, "\n\n")?;
+    Ok((first, second.trim_end_matches('\n')))
+}
+
+/// Checks that `s`, a slice of `input`, only has newlines and bytes from `allowed`.
+pub fn only(input: &str, s: &str, allowed: &str) -> Result<()> {
+    match s.find(|c| c != '\n' && !allowed.contains(c)) {
+        Some(i) => Err(AocError::at(
+            input,
+            &s[i..],
+            format!("expected one of '{allowed}'"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parses a grid followed by a blank line and a block of instructions, both only using the
+/// given characters. The instructions are returned without their newlines.
+pub fn grid_and_instructions(
+    input: &str,
+    grid_chars: &str,
+    instruction_chars: &str,
+) -> Result<(Grid<u8>, Vec<u8>)> {
+    let (grid, instructions) = two_sections(input)?;
+
+    only(input, grid, grid_chars)?;
+    only(input, instructions, instruction_chars)?;
+
+    let instructions = instructions.bytes().filter(|&c| c != b'\n').collect();
+
+    // The grid starts the input, so its lines are the input's.
+    Ok((Grid::parse(grid)?, instructions))
+}
+
+/// Every integer in `s`, a slice of `input`, with a `-` right before it counted as its sign.
+pub fn ints<T: FromStr>(input: &str, s: &str) -> Result<Vec<T>> {
+    let bytes = s.as_bytes();
+    let mut ints = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        ints.push(error::parse(input, &s[start..i])?);
+    }
+
+    Ok(ints)
+}
+
+/// Parses the values in `s`, a slice of `input`, separated by `separator`.
+pub fn list<T: FromStr>(input: &str, s: &str, separator: &str) -> Result<Vec<T>> {
+    s.split(separator).map(|v| error::parse(input, v)).collect()
+}
+
+/// Parses `s`, a slice of `input`, with a pattern like `"Button A: X+{}, Y+{}"`, each `{}` is
+/// a value and everything else has to match exactly.
+///
+/// Panics if the pattern doesn't have `N` values or two values without text between them.
+pub fn record<T: FromStr, const N: usize>(input: &str, s: &str, pattern: &str) -> Result<[T; N]> {
+    let mut literals = pattern.split("{}");
+    let mut rest = error::strip_prefix(input, s, literals.next().unwrap())?;
+    let literals = literals.collect::<Vec<_>>();
+
+    assert_eq!(literals.len(), N, "expected {N} values in '{pattern}'");
+
+    let mut values = Vec::with_capacity(N);
+
+    for (i, literal) in literals.iter().enumerate() {
+        let last = i == N - 1;
+
+        assert!(
+            last || !literal.is_empty(),
+            "values without text between them in '{pattern}'"
+        );
+
+        let (value, after) = if literal.is_empty() {
+            (rest, &rest[rest.len()..])
+        } else if last {
+            let value = rest.strip_suffix(literal).ok_or_else(|| {
+                AocError::at(input, error::end(rest), format!("expected '{literal}'"))
+            })?;
+            (value, &rest[rest.len()..])
+        } else {
+            error::split_once(input, rest, literal)?
+        };
+
+        values.push(error::parse(input, value)?);
+        rest = after;
+    }
+
+    Ok(values
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("there is one value per literal")))
+}
+
+#[test]
+fn test_sections() {
+    let input = "a\nb\n\nc\n\n\n\nd\n";
+
+    assert_eq!(sections(input).collect::<Vec<_>>(), ["a\nb", "c", "d"]);
+    assert_eq!(two_sections(input).unwrap(), ("a\nb", "c\n\n\n\nd"));
+    assert_eq!(
+        two_sections("a\nb\n").unwrap_err().to_string(),
+        "line 1, column 1: expected a blank line"
+    );
+}
+
+#[test]
+fn test_grid_and_instructions() {
+    let input = "#.@\n#..\n\n<^\n>v\n";
+    let (grid, instructions) = grid_and_instructions(input, "#.@", "<>^v").unwrap();
+
+    assert_eq!(grid.find_first(b'@'), Some(glam::ivec2(2, 0)));
+    assert_eq!(instructions, b"<^>v");
+    assert_eq!(
+        grid_and_instructions("#.@\n\n<^\n>x", "#.@", "<>^v")
+            .unwrap_err()
+            .to_string(),
+        "line 4, column 2: expected one of '<>^v'"
+    );
+}
+
+#[test]
+fn test_ints() {
+    let input = "p=0,4 v=3,-3\nx: 1-2, -y 99999999999";
+
+    assert_eq!(
+        ints::<i32>(input, input.lines().next().unwrap()).unwrap(),
+        [0, 4, 3, -3]
+    );
+    assert_eq!(
+        ints::<i32>(input, input).unwrap_err().to_string(),
+        "line 2, column 12: expected i32, found '99999999999'"
+    );
+    assert_eq!(ints::<i64>(input, &input[13..21]).unwrap(), [1, -2]);
+    assert_eq!(list::<u8>(input, "2,4,1", ",").unwrap(), [2, 4, 1]);
+}
+
+#[test]
+fn test_record() {
+    let input = "Button A: X+94, Y+34\nPrize: X=8400, Y=5400\nButton B: X+22 Y+67\n";
+    let mut lines = input.lines();
+
+    assert_eq!(
+        record::<i32, 2>(input, lines.next().unwrap(), "Button A: X+{}, Y+{}").unwrap(),
+        [94, 34]
+    );
+    assert_eq!(
+        record::<i32, 2>(input, lines.next().unwrap(), "Button B: X+{}, Y+{}")
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 1: expected 'Button B: X+'"
+    );
+    assert_eq!(
+        record::<i32, 2>(input, lines.next().unwrap(), "Button B: X+{}, Y+{}")
+            .unwrap_err()
+            .to_string(),
+        "line 3, column 13: expected ', Y+'"
+    );
+    assert_eq!(
+        record::<i8, 4>(input, "p=0,4 v=3,-3", "p={},{} v={},{}").unwrap(),
+        [0, 4, 3, -3]
+    );
+    assert_eq!(
+        record::<u32, 1>(input, "(7", "({})")
+            .unwrap_err()
+            .to_string(),
+        "expected ')'"
+    );
+}
@@ -0,0 +1,384 @@
+use crate::{
+    params::{Param, Params},
+    *,
+};
+use glam::{ivec2, IVec2};
+use std::{fmt::Display, hint::black_box, ops::RangeInclusive, time::Duration};
+
+static DAY11_PARAMS: &[Param] = &[
+    Param {
+        name: "blinks_a",
+        help: "times the stones blink in part a",
+        default: 25,
+        range: 0..=75,
+    },
+    Param {
+        name: "blinks_b",
+        help: "times the stones blink in part b",
+        default: 75,
+        range: 0..=75,
+    },
+];
+
+// Day 14 keeps positions in `i8`s.
+static DAY14_PARAMS: &[Param] = &[
+    Param {
+        name: "width",
+        help: "width of the room, 11 for the example",
+        default: 101,
+        range: 1..=127,
+    },
+    Param {
+        name: "height",
+        help: "height of the room, 7 for the example",
+        default: 103,
+        range: 1..=127,
+    },
+];
+
+static DAY18_PARAMS: &[Param] = &[
+    Param {
+        name: "size",
+        help: "width and height of the memory space, 7 for the example",
+        default: 71,
+        range: 1..=1000,
+    },
+    Param {
+        name: "steps",
+        help: "bytes fallen in part a, 12 for the example",
+        default: 1024,
+        range: 0..=1_000_000,
+    },
+];
+
+static DAY20_PARAMS: &[Param] = &[Param {
+    name: "limit",
+    help: "picoseconds a cheat has to save",
+    default: 100,
+    range: 0..=1_000_000,
+}];
+
+static DAY22_PARAMS: &[Param] = &[Param {
+    name: "iterations",
+    help: "secret numbers generated per buyer",
+    default: 2000,
+    range: 0..=1_000_000,
+}];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::A, Part::B];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => write!(f, "a"),
+            Part::B => write!(f, "b"),
+        }
+    }
+}
+
+pub type Solver = fn(&str, &Params) -> error::Result<String>;
+pub type Renderer = fn(&str, &Params, &mut render::Sink) -> error::Result<()>;
+/// Solves a part `iters` times on input that was parsed once up front, see
+/// [`timing::time_solve`].
+pub type SolveTimer = fn(&str, &Params, Part, u64) -> error::Result<Duration>;
+
+pub struct Day {
+    pub day: u32,
+    pub name: &'static str,
+    /// The compiled-in input, only present with the `embedded-inputs` feature.
+    pub input: Option<&'static str>,
+    pub a: Solver,
+    pub b: Solver,
+    /// The separate parse step of the day, if it has one, so it can be benchmarked on its own.
+    pub parse: Option<fn(&str) -> error::Result<()>>,
+    /// Times the parts without the parse step, present for the days with one.
+    pub solve: Option<SolveTimer>,
+    /// Draws the day's input and solution, as a single frame or an animation.
+    pub render: Option<Renderer>,
+    /// Settings that aren't in the input, see [`params::resolve`].
+    pub params: &'static [Param],
+    /// Parts that don't finish on the real input yet or don't exist, like `25b`, these only run
+    /// when named, e.g. `24b`.
+    pub wip: &'static [Part],
+}
+
+impl Day {
+    pub fn solver(&self, part: Part) -> Solver {
+        match part {
+            Part::A => self.a,
+            Part::B => self.b,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-inputs")]
+macro_rules! embedded {
+    ($module:ident) => {
+        Some($module::INPUT)
+    };
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+macro_rules! embedded {
+    ($module:ident) => {
+        None
+    };
+}
+
+macro_rules! day {
+    ($day:literal, $module:ident, params: $params:expr, $a:expr, $b:expr) => {
+        Day {
+            day: $day,
+            name: stringify!($module),
+            input: embedded!($module),
+            a: |input, params| ($a)(input, params).map(|answer| answer.to_string()),
+            b: |input, params| ($b)(input, params).map(|answer| answer.to_string()),
+            parse: None,
+            solve: None,
+            render: None,
+            params: $params,
+            wip: &[],
+        }
+    };
+    ($day:literal, $module:ident) => {
+        day!($day, $module, $module::a, $module::b)
+    };
+    ($day:literal, $module:ident, $a:expr, $b:expr) => {
+        day!($day, $module, $a, $b, &[])
+    };
+    ($day:literal, $module:ident, $a:expr, $b:expr, $wip:expr) => {
+        Day {
+            wip: $wip,
+            ..day!(
+                $day,
+                $module,
+                params: &[],
+                |input, _: &Params| ($a)(input),
+                |input, _: &Params| ($b)(input)
+            )
+        }
+    };
+}
+
+/// Registers the parse step of a day, `$a` and `$b` solve the parts from the parsed input and
+/// default to `$module::solve_a` and `$module::solve_b`.
+macro_rules! split {
+    ($base:expr, $module:ident) => {
+        split!(
+            $base,
+            $module::parse,
+            |parsed, _| $module::solve_a(parsed),
+            |parsed, _| $module::solve_b(parsed)
+        )
+    };
+    ($base:expr, $parse:path, $a:expr, $b:expr) => {
+        Day {
+            parse: Some(|input| {
+                $parse(input).map(|parsed| {
+                    black_box(parsed);
+                })
+            }),
+            solve: Some(|input, params, part, iters| match part {
+                Part::A => timing::time_solve(input, iters, $parse, |parsed| ($a)(parsed, params)),
+                Part::B => timing::time_solve(input, iters, $parse, |parsed| ($b)(parsed, params)),
+            }),
+            ..$base
+        }
+    };
+}
+
+fn day14_size(params: &Params) -> IVec2 {
+    ivec2(params.get("width") as i32, params.get("height") as i32)
+}
+
+fn day18_size(params: &Params) -> IVec2 {
+    IVec2::splat(params.get("size") as i32)
+}
+
+pub static DAYS: &[Day] = &[
+    day!(1, day01),
+    day!(2, day02),
+    day!(3, day03),
+    day!(4, day04),
+    split!(day!(5, day05), day05),
+    Day {
+        render: Some(|input, _, sink| day06::render(input, sink)),
+        ..split!(day!(6, day06), day06)
+    },
+    split!(day!(7, day07), day07),
+    day!(8, day08),
+    split!(day!(9, day09), day09),
+    split!(day!(10, day10), day10),
+    day!(
+        11,
+        day11,
+        params: DAY11_PARAMS,
+        |input, params: &Params| day11::a(input, params.get("blinks_a") as u32),
+        |input, params: &Params| day11::b(input, params.get("blinks_b") as u32)
+    ),
+    day!(12, day12),
+    split!(day!(13, day13), day13),
+    split!(
+        day!(
+            14,
+            day14,
+            params: DAY14_PARAMS,
+            |input, params: &Params| day14::a(input, day14_size(params)),
+            |input, params: &Params| day14_simd::b(input, day14_size(params))
+        ),
+        day14::Robots::parse,
+        |robots, params: &Params| day14::solve_a(robots, day14_size(params)),
+        |robots, params: &Params| day14_simd::solve_b(robots, day14_size(params))
+    ),
+    Day {
+        render: Some(|input, _, sink| day15::render(input, sink)),
+        ..day!(15, day15)
+    },
+    Day {
+        render: Some(|input, _, sink| day16::render(input, sink)),
+        ..day!(16, day16)
+    },
+    split!(day!(17, day17), day17),
+    split!(
+        day!(
+            18,
+            day18,
+            params: DAY18_PARAMS,
+            |input, params: &Params| day18::a(
+                input,
+                day18_size(params),
+                params.get("steps") as i32
+            ),
+            |input, params: &Params| day18::b(input, day18_size(params))
+        ),
+        day18::parse,
+        |blocks, params: &Params| day18::solve_a(
+            blocks,
+            day18_size(params),
+            params.get("steps") as i32
+        ),
+        |blocks, params: &Params| day18::solve_b(blocks, day18_size(params))
+    ),
+    day!(19, day19),
+    Day {
+        render: Some(|input, params, sink| day20::render(input, params.get("limit") as u32, sink)),
+        ..day!(
+            20,
+            day20,
+            params: DAY20_PARAMS,
+            |input, params: &Params| day20::a(input, params.get("limit") as u32),
+            |input, params: &Params| day20::b(input, params.get("limit") as u32)
+        )
+    },
+    split!(day!(21, day21, day21::a, day21::b, &[Part::B]), day21),
+    split!(
+        day!(
+            22,
+            day22,
+            params: DAY22_PARAMS,
+            |input, params: &Params| day22::a(input, params.get("iterations") as u32),
+            |input, params: &Params| day22::b(input, params.get("iterations") as u32)
+        ),
+        day22::parse,
+        |values, params: &Params| day22::solve_a(values, params.get("iterations") as u32),
+        |values, params: &Params| day22::solve_b(values, params.get("iterations") as u32)
+    ),
+    split!(day!(23, day23), day23),
+    split!(day!(24, day24, day24::a, day24::b, &[Part::B]), day24),
+    split!(day!(25, day25, day25::a, day25::b, &[Part::B]), day25),
+];
+
+pub fn get(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/// Parses a day selection like `5`, `14b`, `3..=12`, `3..12`, `all` or a comma separated list of those.
+pub fn select(spec: &str) -> Result<Vec<(&'static Day, Part)>, String> {
+    let mut selected = Vec::new();
+
+    for item in spec.split(',').map(str::trim) {
+        if item == "all" {
+            add_range(&mut selected, 1..=u32::MAX);
+        } else if let Some((start, end)) = item.split_once("..=") {
+            add_range(&mut selected, parse_day(start)?..=parse_day(end)?);
+        } else if let Some((start, end)) = item.split_once("..") {
+            add_range(
+                &mut selected,
+                parse_day(start)?..=parse_day(end)?.saturating_sub(1),
+            );
+        } else if let Some(day) = item.strip_suffix('a') {
+            selected.push((get_day(parse_day(day)?)?, Part::A));
+        } else if let Some(day) = item.strip_suffix('b') {
+            selected.push((get_day(parse_day(day)?)?, Part::B));
+        } else {
+            let day = get_day(parse_day(item)?)?;
+
+            for part in Part::ALL {
+                if !day.wip.contains(&part) {
+                    selected.push((day, part));
+                }
+            }
+        }
+    }
+
+    if selected.is_empty() {
+        return Err(format!("No days selected by '{spec}'"));
+    }
+
+    // Keep the first occurrence of each part, `5,1,5` runs day 5 once and before day 1.
+    let mut seen = AdventHashSet::default();
+    selected.retain(|(day, part)| seen.insert((day.day, *part)));
+
+    Ok(selected)
+}
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    s.parse::<u32>().map_err(|_| format!("Invalid day '{s}'"))
+}
+
+fn get_day(day: u32) -> Result<&'static Day, String> {
+    get(day).ok_or_else(|| format!("Day {day} is not implemented"))
+}
+
+fn add_range(selected: &mut Vec<(&'static Day, Part)>, range: RangeInclusive<u32>) {
+    for day in DAYS.iter().filter(|d| range.contains(&d.day)) {
+        for part in Part::ALL {
+            if !day.wip.contains(&part) {
+                selected.push((day, part));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_select() {
+    let ids = |spec| {
+        select(spec)
+            .unwrap()
+            .iter()
+            .map(|(d, p)| format!("{}{p}", d.day))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids("5"), ["5a", "5b"]);
+    assert_eq!(ids("14b"), ["14b"]);
+    assert_eq!(ids("3..=4"), ["3a", "3b", "4a", "4b"]);
+    assert_eq!(ids("3..4"), ["3a", "3b"]);
+    assert_eq!(ids("1,2a"), ["1a", "1b", "2a"]);
+    assert_eq!(ids("24"), ["24a"]);
+    assert_eq!(ids("24b"), ["24b"]);
+    assert_eq!(ids("5,1,5"), ["5a", "5b", "1a", "1b"]);
+    assert_eq!(ids("1..=3,2"), ["1a", "1b", "2a", "2b", "3a", "3b"]);
+    assert_eq!(ids("2b,2"), ["2b", "2a"]);
+    assert_eq!(select("all").unwrap().len(), DAYS.len() * 2 - 3);
+    assert!(select("26").is_err());
+    assert!(select("x").is_err());
+}
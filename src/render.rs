@@ -0,0 +1,332 @@
+use crate::{bitgrid::BitGrid, dir::Dir4, error, grid::Grid};
+use crossterm::{
+    queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+};
+use glam::IVec2;
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const DARK_GREY: Rgb = Rgb(60, 60, 60);
+    pub const GREY: Rgb = Rgb(150, 150, 150);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 200, 80);
+    pub const BLUE: Rgb = Rgb(60, 110, 230);
+    pub const CYAN: Rgb = Rgb(42, 161, 152);
+    pub const YELLOW: Rgb = Rgb(240, 200, 40);
+    pub const ORANGE: Rgb = Rgb(230, 120, 30);
+    pub const MAGENTA: Rgb = Rgb(200, 60, 170);
+
+    /// The brightness, as used for grayscale images.
+    pub fn luma(self) -> u8 {
+        ((299 * self.0 as u32 + 587 * self.1 as u32 + 114 * self.2 as u32) / 1000) as u8
+    }
+}
+
+impl From<Rgb> for Color {
+    fn from(rgb: Rgb) -> Color {
+        Color::Rgb {
+            r: rgb.0,
+            g: rgb.1,
+            b: rgb.2,
+        }
+    }
+}
+
+/// Takes the frames of a render one at a time as they're drawn, so a long animation never has to
+/// be kept in memory.
+pub type Sink<'a> = dyn FnMut(Frame) -> error::Result<()> + 'a;
+
+/// A character with its colours. In images a cell is a square in its background colour, or in
+/// its foreground colour when it has none.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cell {
+    pub ch: u8,
+    pub fg: Rgb,
+    pub bg: Option<Rgb>,
+}
+
+impl Cell {
+    pub fn new(ch: u8, fg: Rgb) -> Cell {
+        Cell { ch, fg, bg: None }
+    }
+
+    pub fn color(&self) -> Rgb {
+        self.bg.unwrap_or(self.fg)
+    }
+}
+
+/// The colours of the map characters used across the days.
+pub fn palette(ch: u8) -> Rgb {
+    match ch {
+        b'#' => Rgb::GREY,
+        b'.' => Rgb::DARK_GREY,
+        b'O' | b'[' | b']' => Rgb::ORANGE,
+        b'@' | b'^' | b'>' | b'v' | b'<' => Rgb::YELLOW,
+        b'S' => Rgb::GREEN,
+        b'E' => Rgb::RED,
+        b'0'..=b'9' => Rgb::CYAN,
+        _ => Rgb::WHITE,
+    }
+}
+
+/// A grid of coloured cells, built from a grid and overlays drawn on top of it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Frame {
+    cells: Grid<Cell>,
+}
+
+impl Frame {
+    pub fn blank(width: i32, height: i32) -> Frame {
+        Frame {
+            cells: Grid::new(width, height, Cell::new(b' ', Rgb::BLACK)),
+        }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, style: impl Fn(&T) -> Cell) -> Frame {
+        Frame {
+            cells: Grid {
+                data: grid.data.iter().map(style).collect(),
+                width: grid.width,
+                height: grid.height,
+                outside: Cell::new(b' ', Rgb::BLACK),
+            },
+        }
+    }
+
+    /// A frame of a map, coloured with [`palette`].
+    pub fn chars(grid: &Grid<u8>) -> Frame {
+        Frame::from_grid(grid, |&ch| Cell::new(ch, palette(ch)))
+    }
+
+    pub fn width(&self) -> i32 {
+        self.cells.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.cells.height
+    }
+
+    pub fn get(&self, pos: IVec2) -> Cell {
+        self.cells.get(pos)
+    }
+
+    pub fn set(&mut self, pos: IVec2, cell: Cell) -> bool {
+        self.cells.set(pos, cell)
+    }
+
+    /// Colours the background of `cells`, keeping their characters.
+    pub fn with_background(mut self, cells: impl IntoIterator<Item = IVec2>, color: Rgb) -> Frame {
+        for pos in cells {
+            if let Some(cell) = self.cells.get_mut(pos) {
+                cell.bg = Some(color);
+            }
+        }
+
+        self
+    }
+
+    pub fn with_visited(self, visited: &BitGrid, color: Rgb) -> Frame {
+        self.with_background(visited.iter(), color)
+    }
+
+    /// Draws `ch` in `color` on each of `cells`.
+    pub fn with_highlight(
+        mut self,
+        cells: impl IntoIterator<Item = IVec2>,
+        ch: u8,
+        color: Rgb,
+    ) -> Frame {
+        for pos in cells {
+            if let Some(cell) = self.cells.get_mut(pos) {
+                cell.ch = ch;
+                cell.fg = color;
+            }
+        }
+
+        self
+    }
+
+    /// Draws an arrow on each step of `path` pointing at the next one, the last step is left as
+    /// it is. Steps that aren't next to each other get a `*`.
+    pub fn with_path(mut self, path: &[IVec2], color: Rgb) -> Frame {
+        for step in path.windows(2) {
+            let ch = Dir4::from_offset(step[1] - step[0]).map_or(b'*', Dir4::arrow);
+
+            if let Some(cell) = self.cells.get_mut(step[0]) {
+                *cell = Cell::new(ch, color);
+            }
+        }
+
+        self
+    }
+
+    /// Writes the frame with its colours as terminal escape codes.
+    pub fn write_terminal(&self, out: &mut impl Write) -> io::Result<()> {
+        for row in self.cells.rows() {
+            let mut last = None;
+
+            for cell in row {
+                if last.is_none_or(|(fg, bg)| fg != cell.fg || bg != cell.bg) {
+                    queue!(out, ResetColor, SetForegroundColor(cell.fg.into()))?;
+
+                    if let Some(bg) = cell.bg {
+                        queue!(out, SetBackgroundColor(bg.into()))?;
+                    }
+
+                    last = Some((cell.fg, cell.bg));
+                }
+
+                queue!(out, Print(cell.ch as char))?;
+            }
+
+            queue!(out, ResetColor, Print('\n'))?;
+        }
+
+        out.flush()
+    }
+
+    /// Writes a binary PPM image with each cell as a `scale` by `scale` square.
+    pub fn write_ppm(&self, out: &mut impl Write, scale: u32) -> io::Result<()> {
+        self.write_image(out, scale, "P6", |rgb, pixels| {
+            pixels.extend([rgb.0, rgb.1, rgb.2])
+        })
+    }
+
+    /// Writes a binary PGM image of the brightness of each cell's colour.
+    pub fn write_pgm(&self, out: &mut impl Write, scale: u32) -> io::Result<()> {
+        self.write_image(out, scale, "P5", |rgb, pixels| pixels.push(rgb.luma()))
+    }
+
+    fn write_image(
+        &self,
+        out: &mut impl Write,
+        scale: u32,
+        magic: &str,
+        pixel: impl Fn(Rgb, &mut Vec<u8>),
+    ) -> io::Result<()> {
+        let scale = scale.max(1) as usize;
+        let width = self.width() as usize * scale;
+        let height = self.height() as usize * scale;
+
+        writeln!(out, "{magic}\n{width} {height}\n255")?;
+
+        let mut line = Vec::with_capacity(width * 3);
+
+        for row in self.cells.rows() {
+            line.clear();
+
+            for cell in row {
+                for _ in 0..scale {
+                    pixel(cell.color(), &mut line);
+                }
+            }
+
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+
+        out.flush()
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.rows() {
+            let line = row.iter().map(|cell| cell.ch as char).collect::<String>();
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageFormat {
+    Ppm,
+    Pgm,
+}
+
+/// Writes frames to numbered image files in a directory, for turning into an animation.
+pub struct FrameWriter {
+    dir: PathBuf,
+    format: ImageFormat,
+    scale: u32,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub fn new(dir: &Path, format: ImageFormat, scale: u32) -> io::Result<FrameWriter> {
+        fs::create_dir_all(dir)?;
+
+        Ok(FrameWriter {
+            dir: dir.to_path_buf(),
+            format,
+            scale,
+            next: 0,
+        })
+    }
+
+    /// Writes the next frame, returns the path it was written to.
+    pub fn write(&mut self, frame: &Frame) -> io::Result<PathBuf> {
+        let extension = match self.format {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Pgm => "pgm",
+        };
+        let path = self.dir.join(format!("frame_{:05}.{extension}", self.next));
+        let mut out = io::BufWriter::new(fs::File::create(&path)?);
+
+        match self.format {
+            ImageFormat::Ppm => frame.write_ppm(&mut out, self.scale)?,
+            ImageFormat::Pgm => frame.write_pgm(&mut out, self.scale)?,
+        }
+
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[test]
+fn test_render() {
+    use glam::ivec2;
+
+    let grid = Grid::parse("#.S\n.#E\n").unwrap();
+    let mut visited = BitGrid::new(3, 2);
+    visited.insert(ivec2(1, 0));
+
+    let frame = Frame::chars(&grid)
+        .with_visited(&visited, Rgb::BLUE)
+        .with_path(&[ivec2(0, 1), ivec2(1, 1), ivec2(1, 0)], Rgb::YELLOW)
+        .with_highlight([ivec2(2, 1)], b'X', Rgb::RED);
+
+    assert_eq!(frame.to_string(), "#.S\n>^X\n");
+    assert_eq!(frame.get(ivec2(1, 0)).bg, Some(Rgb::BLUE));
+    assert_eq!(frame.get(ivec2(2, 1)).fg, Rgb::RED);
+
+    let mut ppm = Vec::new();
+    frame.write_ppm(&mut ppm, 2).unwrap();
+    assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+    assert_eq!(ppm.len(), 11 + 6 * 4 * 3);
+    assert_eq!(&ppm[11..14], &[150, 150, 150]);
+
+    let mut pgm = Vec::new();
+    frame.write_pgm(&mut pgm, 1).unwrap();
+    assert_eq!(&pgm[..11], b"P5\n3 2\n255\n");
+    assert_eq!(pgm[11], Rgb::GREY.luma());
+
+    let mut terminal = Vec::new();
+    frame.write_terminal(&mut terminal).unwrap();
+    assert!(String::from_utf8(terminal)
+        .unwrap()
+        .contains("\x1b[38;2;150;150;150m#"));
+}
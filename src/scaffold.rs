@@ -0,0 +1,181 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Template for new days, see `src/day.rs`.
+pub static TEMPLATE: &str = include_str!("day.rs");
+
+/// The name of the module of `day`, e.g. `day07`.
+pub fn module_name(day: u32) -> String {
+    format!("day{day:02}")
+}
+
+/// The template with the input paths of `day` filled in.
+pub fn day_source(day: u32) -> String {
+    TEMPLATE
+        .replace("\"../input/.txt\"", &format!("\"../input/{day}.txt\""))
+        .replace(
+            "\"../input/_test.txt\"",
+            &format!("\"../input/{day}_test.txt\""),
+        )
+}
+
+/// Adds `pub mod <module>;` to the contents of `src/days/mod.rs`, keeping the modules sorted.
+pub fn add_module(mod_rs: &str, module: &str) -> Result<String, String> {
+    let line = format!("pub mod {module};");
+
+    if mod_rs.lines().any(|l| l.trim() == line) {
+        return Err(format!("src/days/mod.rs already has {module}"));
+    }
+
+    let mut lines = mod_rs.lines().collect::<Vec<_>>();
+    let index = lines
+        .iter()
+        .position(|l| l.starts_with("pub mod ") && *l > line.as_str())
+        .unwrap_or_else(|| {
+            lines
+                .iter()
+                .rposition(|l| l.starts_with("pub mod "))
+                .map_or(0, |i| i + 1)
+        });
+
+    lines.insert(index, &line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The day an entry of `DAYS` registers, e.g. 17 for `Day { ..day!(17, day17) }`.
+fn entry_day(entry: &str) -> Option<u32> {
+    let (_, rest) = entry.split_once("day!(")?;
+    let end = rest.find(',')?;
+    rest[..end].trim().parse().ok()
+}
+
+/// Adds `day!(<day>, <module>),` to the `DAYS` table in the contents of `src/registry.rs`,
+/// keeping the days sorted.
+pub fn register(registry: &str, day: u32) -> Result<String, String> {
+    let start = registry
+        .find("pub static DAYS: &[Day] = &[\n")
+        .ok_or("Could not find DAYS in src/registry.rs")?;
+    let body_start = start + registry[start..].find('\n').unwrap() + 1;
+    let body_end = body_start
+        + registry[body_start..]
+            .find("\n];")
+            .ok_or("Could not find the end of DAYS in src/registry.rs")?
+        + 1;
+
+    // Every entry starts on a line indented by exactly four spaces.
+    let mut entries = Vec::new();
+    let mut offset = body_start;
+
+    for line in registry[body_start..body_end].split_inclusive('\n') {
+        if line.starts_with("    ") && !line.starts_with("     ") {
+            entries.push(offset);
+        }
+        offset += line.len();
+    }
+
+    let mut insert_at = body_end;
+
+    for (i, &entry_start) in entries.iter().enumerate() {
+        let entry_end = entries.get(i + 1).copied().unwrap_or(body_end);
+
+        match entry_day(&registry[entry_start..entry_end]) {
+            Some(d) if d == day => return Err(format!("Day {day} is already registered")),
+            Some(d) if d > day => {
+                insert_at = entry_start;
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let mut res = registry.to_string();
+    res.insert_str(
+        insert_at,
+        &format!("    day!({day}, {}),\n", module_name(day)),
+    );
+
+    Ok(res)
+}
+
+/// Creates `src/days/dayNN.rs` from the template, adds it to `src/days/mod.rs` and the registry
+/// and creates empty input files. Nothing is written if any of it already exists.
+pub fn scaffold(root: &Path, day: u32) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("Invalid day {day}, expected 1 to 25"));
+    }
+
+    let module = module_name(day);
+    let source = root.join("src/days").join(format!("{module}.rs"));
+    let mod_rs = root.join("src/days/mod.rs");
+    let registry = root.join("src/registry.rs");
+    let input = root.join("src/input").join(format!("{day}.txt"));
+    let test_input = root.join("src/input").join(format!("{day}_test.txt"));
+
+    for path in [&source, &input, &test_input] {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()));
+        }
+    }
+
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))
+    };
+    let write = |path: &Path, contents: &str| {
+        fs::write(path, contents).map_err(|e| format!("Could not write {}: {e}", path.display()))
+    };
+
+    let new_mod_rs = add_module(&read(&mod_rs)?, &module)?;
+    let new_registry = register(&read(&registry)?, day)?;
+
+    write(&source, &day_source(day))?;
+    write(&input, "")?;
+    write(&test_input, "")?;
+    write(&mod_rs, &new_mod_rs)?;
+    write(&registry, &new_registry)?;
+
+    Ok(vec![source, input, test_input, mod_rs, registry])
+}
+
+#[test]
+fn test_day_source() {
+    let source = day_source(7);
+
+    assert!(source.contains("include_str!(\"../input/7.txt\")"));
+    assert!(source.contains("include_str!(\"../input/7_test.txt\")"));
+}
+
+#[test]
+fn test_add_module() {
+    let mod_rs = "pub mod day01;\npub mod day03;\n";
+
+    assert_eq!(
+        add_module(mod_rs, "day02").unwrap(),
+        "pub mod day01;\npub mod day02;\npub mod day03;\n"
+    );
+    assert_eq!(
+        add_module(mod_rs, "day04").unwrap(),
+        "pub mod day01;\npub mod day03;\npub mod day04;\n"
+    );
+    assert!(add_module(mod_rs, "day03").is_err());
+}
+
+#[test]
+fn test_register() {
+    let registry = "pub static DAYS: &[Day] = &[\n    day!(1, day01),\n    Day {\n        parse: None,\n        ..day!(3, day03)\n    },\n    day!(\n        5,\n        day05,\n        day05::a,\n        day05::b\n    ),\n];\n";
+
+    assert_eq!(
+        register(registry, 4).unwrap(),
+        registry.replace(
+            "    day!(\n        5",
+            "    day!(4, day04),\n    day!(\n        5"
+        )
+    );
+    assert_eq!(
+        register(registry, 25).unwrap(),
+        registry.replace("\n];", "\n    day!(25, day25),\n];")
+    );
+    assert!(register(registry, 3).is_err());
+}
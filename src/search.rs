@@ -0,0 +1,518 @@
+use crate::{AdventHashMap, AdventHashSet};
+use smallvec::{smallvec, SmallVec};
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+    slice,
+};
+
+/// A path found by [`astar`], [`dijkstra`] or [`bfs`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Found<S> {
+    pub cost: u32,
+    /// From the start to the goal, both included.
+    pub path: Vec<S>,
+    /// How many states were taken off the open set and expanded.
+    pub explored: usize,
+}
+
+struct Node<S> {
+    state: S,
+    parent: usize,
+    cost: u32,
+}
+
+fn path<S: Copy>(nodes: &[Node<S>], mut i: usize) -> Vec<S> {
+    let mut path = vec![nodes[i].state];
+
+    while nodes[i].parent != usize::MAX {
+        i = nodes[i].parent;
+        path.push(nodes[i].state);
+    }
+
+    path.reverse();
+    path
+}
+
+/// Finds a cheapest path from `start` to a state `is_goal` accepts. `neighbours` returns the
+/// states reachable from a state with the cost of getting there and `heuristic` must never
+/// overestimate the remaining cost, or the path found may not be the cheapest.
+pub fn astar<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u32,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S>>
+where
+    S: Copy + Eq + Hash,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    let mut nodes = vec![Node {
+        state: start,
+        parent: usize::MAX,
+        cost: 0,
+    }];
+    let mut indices = AdventHashMap::default();
+    indices.insert(start, 0);
+
+    let mut open_set = BinaryHeap::new();
+    open_set.push(Reverse((heuristic(&start), 0, 0)));
+
+    let mut explored = 0;
+
+    while let Some(Reverse((_, cost, i))) = open_set.pop() {
+        // Already reached more cheaply since it was pushed.
+        if cost > nodes[i].cost {
+            continue;
+        }
+
+        explored += 1;
+
+        let state = nodes[i].state;
+
+        if is_goal(&state) {
+            return Some(Found {
+                cost,
+                path: path(&nodes, i),
+                explored,
+            });
+        }
+
+        for (neighbour, step_cost) in neighbours(&state) {
+            let tentative_cost = cost + step_cost;
+
+            let j = match indices.entry(neighbour) {
+                Entry::Vacant(e) => {
+                    e.insert(nodes.len());
+                    nodes.push(Node {
+                        state: neighbour,
+                        parent: i,
+                        cost: tentative_cost,
+                    });
+                    nodes.len() - 1
+                }
+                Entry::Occupied(e) => {
+                    let j = *e.get();
+                    if tentative_cost >= nodes[j].cost {
+                        continue;
+                    }
+                    nodes[j].parent = i;
+                    nodes[j].cost = tentative_cost;
+                    j
+                }
+            };
+
+            open_set.push(Reverse((
+                tentative_cost + heuristic(&neighbour),
+                tentative_cost,
+                j,
+            )));
+        }
+    }
+
+    None
+}
+
+/// [`astar`] without a heuristic.
+pub fn dijkstra<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S>>
+where
+    S: Copy + Eq + Hash,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// Finds a path with the fewest steps from `start` to a state `is_goal` accepts, every step
+/// costs 1.
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S>>
+where
+    S: Copy + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut nodes = vec![Node {
+        state: start,
+        parent: usize::MAX,
+        cost: 0,
+    }];
+    let mut indices = AdventHashMap::default();
+    indices.insert(start, 0);
+
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+        let state = nodes[i].state;
+        let cost = nodes[i].cost;
+
+        if is_goal(&state) {
+            return Some(Found {
+                cost,
+                path: path(&nodes, i),
+                // States are expanded in the order they were found.
+                explored: i + 1,
+            });
+        }
+
+        for neighbour in neighbours(&state) {
+            if let Entry::Vacant(e) = indices.entry(neighbour) {
+                e.insert(nodes.len());
+                queue.push_back(nodes.len());
+                nodes.push(Node {
+                    state: neighbour,
+                    parent: i,
+                    cost: cost + 1,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+struct DagNode<S> {
+    state: S,
+    cost: u32,
+    predecessors: SmallVec<[usize; 2]>,
+}
+
+/// Every cheapest path from the start to the goals, found by [`dijkstra_all`]. Each state on
+/// them knows all the states it can be reached from at its cost.
+pub struct Predecessors<S> {
+    nodes: Vec<DagNode<S>>,
+    /// The states in the order they were expanded, predecessors come before their successors.
+    order: Vec<usize>,
+    goals: Vec<usize>,
+}
+
+/// Like [`dijkstra`] but keeps every cheapest way to reach each state, and every goal reached at
+/// the lowest cost. All step costs must be above 0.
+pub fn dijkstra_all<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Predecessors<S>>
+where
+    S: Copy + Eq + Hash,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    let mut nodes = vec![DagNode {
+        state: start,
+        cost: 0,
+        predecessors: SmallVec::new(),
+    }];
+    let mut indices = AdventHashMap::default();
+    indices.insert(start, 0);
+
+    let mut open_set = BinaryHeap::new();
+    open_set.push(Reverse((0, 0)));
+
+    let mut order = Vec::new();
+    let mut goals = Vec::<usize>::new();
+
+    while let Some(Reverse((cost, i))) = open_set.pop() {
+        if cost > nodes[i].cost {
+            continue;
+        }
+
+        if goals.first().is_some_and(|&goal| cost > nodes[goal].cost) {
+            break;
+        }
+
+        order.push(i);
+
+        let state = nodes[i].state;
+
+        if is_goal(&state) {
+            goals.push(i);
+            continue;
+        }
+
+        for (neighbour, step_cost) in neighbours(&state) {
+            let tentative_cost = cost + step_cost;
+
+            match indices.entry(neighbour) {
+                Entry::Vacant(e) => {
+                    e.insert(nodes.len());
+                    open_set.push(Reverse((tentative_cost, nodes.len())));
+                    nodes.push(DagNode {
+                        state: neighbour,
+                        cost: tentative_cost,
+                        predecessors: smallvec![i],
+                    });
+                }
+                Entry::Occupied(e) => {
+                    let node = &mut nodes[*e.get()];
+
+                    if tentative_cost < node.cost {
+                        node.cost = tentative_cost;
+                        node.predecessors = smallvec![i];
+                        open_set.push(Reverse((tentative_cost, *e.get())));
+                    } else if tentative_cost == node.cost {
+                        node.predecessors.push(i);
+                    }
+                }
+            }
+        }
+    }
+
+    (!goals.is_empty()).then_some(Predecessors {
+        nodes,
+        order,
+        goals,
+    })
+}
+
+impl<S: Copy + Eq + Hash> Predecessors<S> {
+    /// The cost of the cheapest paths.
+    pub fn cost(&self) -> u32 {
+        self.nodes[self.goals[0]].cost
+    }
+
+    /// The goals reached at the lowest cost.
+    pub fn goals(&self) -> impl Iterator<Item = S> + '_ {
+        self.goals.iter().map(|&i| self.nodes[i].state)
+    }
+
+    /// How many cheapest paths lead from the start to each expanded state.
+    fn path_counts(&self) -> Vec<u64> {
+        let mut counts = vec![0; self.nodes.len()];
+        counts[0] = 1;
+
+        for &i in &self.order[1..] {
+            counts[i] = self.nodes[i].predecessors.iter().map(|&p| counts[p]).sum();
+        }
+
+        counts
+    }
+
+    /// The number of distinct cheapest paths, without enumerating them.
+    pub fn count_paths(&self) -> u64 {
+        let counts = self.path_counts();
+        self.goals.iter().map(|&i| counts[i]).sum()
+    }
+
+    /// Every cheapest path, from the start to a goal, one at a time.
+    pub fn paths(&self) -> Paths<'_, S> {
+        Paths {
+            predecessors: self,
+            goals: self.goals.iter(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// The states on any cheapest path.
+    pub fn states_on_paths(&self) -> AdventHashSet<S> {
+        let mut on_path = vec![false; self.nodes.len()];
+        let mut stack = self.goals.clone();
+
+        while let Some(i) = stack.pop() {
+            if !on_path[i] {
+                on_path[i] = true;
+                stack.extend(&self.nodes[i].predecessors);
+            }
+        }
+
+        on_path
+            .iter()
+            .zip(&self.nodes)
+            .filter(|(on_path, _)| **on_path)
+            .map(|(_, node)| node.state)
+            .collect()
+    }
+
+    /// One of the cheapest paths, each with the same chance of being picked.
+    pub fn random_path(&self, rng: &mut fastrand::Rng) -> Vec<S> {
+        let counts = self.path_counts();
+
+        // Picks one of `candidates` with a chance proportional to the paths leading to it.
+        let mut pick = |candidates: &[usize]| {
+            let total = candidates.iter().map(|&i| counts[i]).sum::<u64>();
+            let mut n = rng.u64(..total);
+
+            for &i in candidates {
+                if n < counts[i] {
+                    return i;
+                }
+                n -= counts[i];
+            }
+
+            unreachable!()
+        };
+
+        let mut i = pick(&self.goals);
+        let mut path = vec![self.nodes[i].state];
+
+        while i != 0 {
+            i = pick(&self.nodes[i].predecessors);
+            path.push(self.nodes[i].state);
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Iterator over the cheapest paths, see [`Predecessors::paths`].
+pub struct Paths<'a, S> {
+    predecessors: &'a Predecessors<S>,
+    goals: slice::Iter<'a, usize>,
+    /// The path back from a goal being walked, with the next predecessor to try for each state.
+    stack: Vec<(usize, usize)>,
+}
+
+impl<S: Copy> Iterator for Paths<'_, S> {
+    type Item = Vec<S>;
+
+    fn next(&mut self) -> Option<Vec<S>> {
+        let nodes = &self.predecessors.nodes;
+
+        loop {
+            let Some(&mut (i, ref mut next)) = self.stack.last_mut() else {
+                self.stack.push((*self.goals.next()?, 0));
+                continue;
+            };
+
+            if i == 0 {
+                let path = self.stack.iter().rev().map(|&(i, _)| nodes[i].state);
+                let path = path.collect();
+                self.stack.pop();
+                return Some(path);
+            }
+
+            match nodes[i].predecessors.get(*next) {
+                Some(&predecessor) => {
+                    *next += 1;
+                    self.stack.push((predecessor, 0));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_grid_neighbours(
+    map: &crate::grid::Grid<u8>,
+) -> impl FnMut(&glam::IVec2) -> Vec<(glam::IVec2, u32)> + '_ {
+    move |&pos| {
+        crate::dir::neighbours4(pos)
+            .filter(|&next| map.get(next) == b'.')
+            .map(|next| (next, 1))
+            .collect()
+    }
+}
+
+#[test]
+fn test_astar() {
+    use crate::{dir::manhattan, grid::Grid};
+    use glam::ivec2;
+
+    let map = Grid::parse("....\n.##.\n.#..\n...#\n")
+        .unwrap()
+        .with_outside(b'#');
+    let end = ivec2(2, 3);
+
+    let found = astar(
+        ivec2(0, 0),
+        test_grid_neighbours(&map),
+        |&pos| manhattan(pos, end),
+        |&pos| pos == end,
+    )
+    .unwrap();
+
+    assert_eq!(found.cost, 5);
+    assert_eq!(found.path.len(), 6);
+    assert_eq!(found.path.first(), Some(&ivec2(0, 0)));
+    assert_eq!(found.path.last(), Some(&end));
+
+    let by_dijkstra = dijkstra(ivec2(0, 0), test_grid_neighbours(&map), |&pos| pos == end).unwrap();
+
+    assert_eq!(by_dijkstra.cost, 5);
+    assert!(by_dijkstra.explored >= found.explored);
+
+    assert_eq!(
+        astar(
+            ivec2(0, 0),
+            test_grid_neighbours(&map),
+            |_| 0,
+            |&pos| pos == ivec2(3, 3)
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_weighted() {
+    // Going through b is shorter but more expensive.
+    let edges = |&s: &char| match s {
+        'a' => vec![('b', 10), ('c', 1)],
+        'c' => vec![('d', 1)],
+        'd' => vec![('e', 1)],
+        'b' => vec![('e', 1)],
+        _ => vec![],
+    };
+
+    let found = dijkstra('a', edges, |&s| s == 'e').unwrap();
+
+    assert_eq!(found.cost, 3);
+    assert_eq!(found.path, ['a', 'c', 'd', 'e']);
+
+    let found = bfs('a', |s| edges(s).into_iter().map(|(n, _)| n), |&s| s == 'e').unwrap();
+
+    assert_eq!(found.cost, 2);
+    assert_eq!(found.path, ['a', 'b', 'e']);
+}
+
+#[test]
+fn test_predecessors() {
+    // Three cheapest paths from a to f, one of them through c and e.
+    let edges = |&s: &char| match s {
+        'a' => vec![('b', 1), ('c', 1)],
+        'b' => vec![('d', 1), ('e', 1)],
+        'c' => vec![('e', 1), ('g', 5)],
+        'd' => vec![('f', 1)],
+        'e' => vec![('f', 1)],
+        _ => vec![],
+    };
+
+    let dag = dijkstra_all('a', edges, |&s| s == 'f').unwrap();
+
+    assert_eq!(dag.cost(), 3);
+    assert_eq!(dag.goals().collect::<Vec<_>>(), ['f']);
+    assert_eq!(dag.count_paths(), 3);
+
+    let mut paths = dag.paths().collect::<Vec<_>>();
+    paths.sort();
+
+    assert_eq!(
+        paths,
+        [
+            ['a', 'b', 'd', 'f'],
+            ['a', 'b', 'e', 'f'],
+            ['a', 'c', 'e', 'f']
+        ]
+    );
+
+    let mut states = dag.states_on_paths().into_iter().collect::<Vec<_>>();
+    states.sort();
+
+    assert_eq!(states, ['a', 'b', 'c', 'd', 'e', 'f']);
+
+    let mut rng = fastrand::Rng::with_seed(7);
+
+    for _ in 0..10 {
+        assert!(paths.contains(&dag.random_path(&mut rng)));
+    }
+
+    assert!(dijkstra_all('a', edges, |&s| s == 'x').is_none());
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+#[cfg(feature = "spans")]
+use std::{cell::RefCell, time::Instant};
+
+/// Whether spans are timed, without the `spans` feature [`enter`] and [`collect`] do nothing.
+pub const ENABLED: bool = cfg!(feature = "spans");
+
+/// The time spent in all spans with the same name during one [`collect`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SpanStats {
+    pub name: &'static str,
+    pub calls: u32,
+    pub total: Duration,
+}
+
+#[cfg(feature = "spans")]
+thread_local! {
+    static SPANS: RefCell<Option<Vec<SpanStats>>> = const { RefCell::new(None) };
+}
+
+/// Times a phase of a solution until it's dropped, see [`enter`].
+#[must_use = "the span ends when dropped"]
+pub struct Span {
+    #[cfg(feature = "spans")]
+    name: &'static str,
+    #[cfg(feature = "spans")]
+    start: Instant,
+}
+
+/// Starts a span named `name`, e.g. `let _span = span::enter("parse");` times the rest of the
+/// block. Spans can nest, nested spans with the same name are counted twice.
+#[inline(always)]
+#[cfg_attr(not(feature = "spans"), allow(unused_variables))]
+pub fn enter(name: &'static str) -> Span {
+    Span {
+        #[cfg(feature = "spans")]
+        name,
+        #[cfg(feature = "spans")]
+        start: Instant::now(),
+    }
+}
+
+/// Times `f` as a span named `name`.
+#[inline(always)]
+pub fn time<R>(name: &'static str, f: impl FnOnce() -> R) -> R {
+    let _span = enter(name);
+    f()
+}
+
+#[cfg(feature = "spans")]
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+
+        SPANS.with_borrow_mut(|spans| {
+            let Some(spans) = spans else {
+                return;
+            };
+
+            match spans.iter_mut().find(|s| s.name == self.name) {
+                Some(stats) => {
+                    stats.calls += 1;
+                    stats.total += elapsed;
+                }
+                None => spans.push(SpanStats {
+                    name: self.name,
+                    calls: 1,
+                    total: elapsed,
+                }),
+            }
+        });
+    }
+}
+
+/// Runs `f`, collecting the spans it enters on this thread by name in the order they first
+/// ended.
+#[cfg(feature = "spans")]
+pub fn collect<R>(f: impl FnOnce() -> R) -> (R, Vec<SpanStats>) {
+    let previous = SPANS.replace(Some(Vec::new()));
+    let res = f();
+    let spans = SPANS.replace(previous).unwrap_or_default();
+
+    (res, spans)
+}
+
+#[cfg(not(feature = "spans"))]
+pub fn collect<R>(f: impl FnOnce() -> R) -> (R, Vec<SpanStats>) {
+    (f(), Vec::new())
+}
+
+#[cfg(feature = "spans")]
+#[test]
+fn test_spans() {
+    let (res, spans) = collect(|| {
+        let _outer = enter("solve");
+
+        for i in 0..3 {
+            time("step", || std::hint::black_box(i));
+        }
+
+        7
+    });
+
+    assert_eq!(res, 7);
+    assert_eq!(
+        spans.iter().map(|s| (s.name, s.calls)).collect::<Vec<_>>(),
+        [("step", 3), ("solve", 1)]
+    );
+    assert!(spans[1].total >= spans[0].total);
+
+    let _ignored = enter("outside");
+}
+
+#[cfg(not(feature = "spans"))]
+#[test]
+fn test_spans_disabled() {
+    assert_eq!(std::mem::size_of::<Span>(), 0);
+    assert_eq!(collect(|| time("solve", || 7)), (7, Vec::new()));
+}
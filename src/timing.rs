@@ -0,0 +1,577 @@
+use crate::{
+    error,
+    memory::{self, AllocStats},
+    params::Params,
+    registry::{Part, Solver},
+    span::SpanStats,
+};
+use std::{
+    fmt::Write,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_arg(arg: &str) -> Result<Format, String> {
+        match arg {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "Unknown format '{arg}', expected table, json or csv"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        assert!(!samples.is_empty());
+
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        Stats {
+            runs: n,
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / n as u32,
+            p95: samples[(n * 95).div_ceil(100) - 1],
+        }
+    }
+}
+
+pub struct Timing {
+    pub day: u32,
+    pub part: Part,
+    pub name: String,
+    pub input: String,
+    pub answer: String,
+    pub stats: Stats,
+    /// The allocations of one more run, when they were counted.
+    pub alloc: Option<AllocStats>,
+    /// The spans of one more run, empty unless they were collected.
+    pub spans: Vec<SpanStats>,
+}
+
+/// Runs `f` `warmup` times without measuring, then `runs` times measuring each run.
+pub fn measure(
+    f: Solver,
+    input: &str,
+    params: &Params,
+    warmup: usize,
+    runs: usize,
+) -> error::Result<(String, Stats)> {
+    for _ in 0..warmup {
+        f(input, params)?;
+    }
+
+    let mut answer = String::new();
+    let mut samples = Vec::with_capacity(runs.max(1));
+
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        answer = f(input, params)?;
+        samples.push(start.elapsed());
+    }
+
+    Ok((answer, Stats::from_samples(&mut samples)))
+}
+
+/// Parses `input` once, then times `solve` on a clone of the parsed input `iters` times. Only
+/// the solving is timed, not the parsing or cloning.
+pub fn time_solve<'a, P: Clone, T>(
+    input: &'a str,
+    iters: u64,
+    parse: impl Fn(&'a str) -> error::Result<P>,
+    solve: impl Fn(P) -> error::Result<T>,
+) -> error::Result<Duration> {
+    let parsed = parse(input)?;
+    let mut total = Duration::ZERO;
+
+    for _ in 0..iters {
+        let parsed = black_box(parsed.clone());
+        let start = Instant::now();
+        black_box(solve(parsed)?);
+        total += start.elapsed();
+    }
+
+    Ok(total)
+}
+
+/// The sum of the median times.
+pub fn total(timings: &[Timing]) -> Duration {
+    timings.iter().map(|t| t.stats.median).sum()
+}
+
+/// The phases of all the timed parts of a day.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DaySpans {
+    pub day: u32,
+    /// The sum of the median times of the parts.
+    pub median: Duration,
+    /// The spans of the parts combined by name.
+    pub spans: Vec<SpanStats>,
+}
+
+/// The spans of each day with any, in the order the days were first timed.
+pub fn day_spans(timings: &[Timing]) -> Vec<DaySpans> {
+    let mut days = Vec::<DaySpans>::new();
+
+    for t in timings.iter().filter(|t| !t.spans.is_empty()) {
+        let index = match days.iter().position(|d| d.day == t.day) {
+            Some(index) => index,
+            None => {
+                days.push(DaySpans {
+                    day: t.day,
+                    median: Duration::ZERO,
+                    spans: Vec::new(),
+                });
+                days.len() - 1
+            }
+        };
+        let day = &mut days[index];
+
+        day.median += t.stats.median;
+
+        for span in &t.spans {
+            match day.spans.iter_mut().find(|s| s.name == span.name) {
+                Some(stats) => {
+                    stats.calls += span.calls;
+                    stats.total += span.total;
+                }
+                None => day.spans.push(span.clone()),
+            }
+        }
+    }
+
+    days
+}
+
+fn us(d: Duration) -> String {
+    format!("{:.1}", d.as_secs_f64() * 1e6)
+}
+
+fn percent(part: Duration, whole: Duration) -> String {
+    if whole.is_zero() {
+        "-".to_string()
+    } else {
+        format!("{:.1}%", part.as_secs_f64() / whole.as_secs_f64() * 100.0)
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+
+    res.push('"');
+    res
+}
+
+fn json_spans(spans: &[SpanStats]) -> String {
+    let spans = spans
+        .iter()
+        .map(|s| {
+            format!(
+                "{{\"name\": {}, \"calls\": {}, \"ns\": {}}}",
+                json_string(s.name),
+                s.calls,
+                s.total.as_nanos()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!("[{}]", spans.join(", "))
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn report(timings: &[Timing], format: Format) -> String {
+    let mut out = String::new();
+    let counted = timings.iter().any(|t| t.alloc.is_some());
+    let spans = timings.iter().any(|t| !t.spans.is_empty());
+
+    match format {
+        Format::Table => {
+            let name_width = timings.iter().map(|t| t.name.len()).max().unwrap_or(0);
+
+            write!(
+                out,
+                "{:name_width$}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                "", "runs", "min us", "median us", "mean us", "p95 us"
+            )
+            .unwrap();
+
+            if counted {
+                write!(
+                    out,
+                    "  {:>9}  {:>10}  {:>10}",
+                    "allocs", "allocated", "peak"
+                )
+                .unwrap();
+            }
+
+            writeln!(out, "  answer").unwrap();
+
+            for t in timings {
+                write!(
+                    out,
+                    "{:name_width$}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                    t.name,
+                    t.stats.runs,
+                    us(t.stats.min),
+                    us(t.stats.median),
+                    us(t.stats.mean),
+                    us(t.stats.p95),
+                )
+                .unwrap();
+
+                if counted {
+                    let alloc = t.alloc.unwrap_or_default();
+                    write!(
+                        out,
+                        "  {:>9}  {:>10}  {:>10}",
+                        alloc.allocs,
+                        memory::format_bytes(alloc.bytes),
+                        memory::format_bytes(alloc.peak)
+                    )
+                    .unwrap();
+                }
+
+                writeln!(out, "  {}", t.answer).unwrap();
+            }
+
+            writeln!(out, "Total median time {} us", us(total(timings))).unwrap();
+
+            if spans {
+                let span_width = timings
+                    .iter()
+                    .flat_map(|t| t.spans.iter().map(|s| t.name.len() + 1 + s.name.len()))
+                    .max()
+                    .unwrap_or(0);
+
+                writeln!(
+                    out,
+                    "\n{:span_width$}  {:>5}  {:>10}  {:>9}",
+                    "phase", "calls", "total us", "of median"
+                )
+                .unwrap();
+
+                for t in timings {
+                    for s in &t.spans {
+                        writeln!(
+                            out,
+                            "{:span_width$}  {:>5}  {:>10}  {:>9}",
+                            format!("{} {}", t.name, s.name),
+                            s.calls,
+                            us(s.total),
+                            percent(s.total, t.stats.median)
+                        )
+                        .unwrap();
+                    }
+                }
+
+                writeln!(
+                    out,
+                    "\n{:span_width$}  {:>5}  {:>10}  {:>9}",
+                    "day phase", "calls", "total us", "of median"
+                )
+                .unwrap();
+
+                for d in day_spans(timings) {
+                    for s in &d.spans {
+                        writeln!(
+                            out,
+                            "{:span_width$}  {:>5}  {:>10}  {:>9}",
+                            format!("day{:02} {}", d.day, s.name),
+                            s.calls,
+                            us(s.total),
+                            percent(s.total, d.median)
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+        Format::Json => {
+            writeln!(out, "{{").unwrap();
+            writeln!(out, "  \"results\": [").unwrap();
+
+            for (i, t) in timings.iter().enumerate() {
+                let alloc = match t.alloc {
+                    Some(a) => format!(
+                        ", \"allocs\": {}, \"alloc_bytes\": {}, \"peak_bytes\": {}",
+                        a.allocs, a.bytes, a.peak
+                    ),
+                    None => String::new(),
+                };
+                let spans = if t.spans.is_empty() {
+                    String::new()
+                } else {
+                    format!(", \"spans\": {}", json_spans(&t.spans))
+                };
+
+                writeln!(
+                    out,
+                    "    {{\"day\": {}, \"part\": \"{}\", \"input\": {}, \"answer\": {}, \"runs\": {}, \"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"p95_ns\": {}{}{}}}{}",
+                    t.day,
+                    t.part,
+                    json_string(&t.input),
+                    json_string(&t.answer),
+                    t.stats.runs,
+                    t.stats.min.as_nanos(),
+                    t.stats.median.as_nanos(),
+                    t.stats.mean.as_nanos(),
+                    t.stats.p95.as_nanos(),
+                    alloc,
+                    spans,
+                    if i + 1 < timings.len() { "," } else { "" }
+                )
+                .unwrap();
+            }
+
+            writeln!(out, "  ],").unwrap();
+
+            if spans {
+                let days = day_spans(timings);
+
+                writeln!(out, "  \"day_spans\": [").unwrap();
+
+                for (i, d) in days.iter().enumerate() {
+                    writeln!(
+                        out,
+                        "    {{\"day\": {}, \"median_ns\": {}, \"spans\": {}}}{}",
+                        d.day,
+                        d.median.as_nanos(),
+                        json_spans(&d.spans),
+                        if i + 1 < days.len() { "," } else { "" }
+                    )
+                    .unwrap();
+                }
+
+                writeln!(out, "  ],").unwrap();
+            }
+
+            writeln!(out, "  \"total_median_ns\": {}", total(timings).as_nanos()).unwrap();
+            writeln!(out, "}}").unwrap();
+        }
+        Format::Csv => {
+            write!(
+                out,
+                "day,part,input,answer,runs,min_ns,median_ns,mean_ns,p95_ns"
+            )
+            .unwrap();
+
+            if counted {
+                write!(out, ",allocs,alloc_bytes,peak_bytes").unwrap();
+            }
+
+            if spans {
+                write!(out, ",spans").unwrap();
+            }
+
+            writeln!(out).unwrap();
+
+            for t in timings {
+                write!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    t.day,
+                    t.part,
+                    csv_field(&t.input),
+                    csv_field(&t.answer),
+                    t.stats.runs,
+                    t.stats.min.as_nanos(),
+                    t.stats.median.as_nanos(),
+                    t.stats.mean.as_nanos(),
+                    t.stats.p95.as_nanos()
+                )
+                .unwrap();
+
+                if counted {
+                    let alloc = t.alloc.unwrap_or_default();
+                    write!(out, ",{},{},{}", alloc.allocs, alloc.bytes, alloc.peak).unwrap();
+                }
+
+                if spans {
+                    let spans = t
+                        .spans
+                        .iter()
+                        .map(|s| format!("{}={}", s.name, s.total.as_nanos()))
+                        .collect::<Vec<_>>();
+                    write!(out, ",{}", csv_field(&spans.join(";"))).unwrap();
+                }
+
+                writeln!(out).unwrap();
+            }
+        }
+    }
+
+    out
+}
+
+#[test]
+fn test_stats() {
+    let mut samples = [5, 1, 4, 2, 3, 100].map(Duration::from_micros).to_vec();
+
+    let stats = Stats::from_samples(&mut samples);
+
+    assert_eq!(stats.runs, 6);
+    assert_eq!(stats.min, Duration::from_micros(1));
+    assert_eq!(stats.median, Duration::from_nanos(3500));
+    assert_eq!(stats.mean, Duration::from_nanos(19166));
+    assert_eq!(stats.p95, Duration::from_micros(100));
+
+    let stats = Stats::from_samples(&mut [Duration::from_micros(7)]);
+
+    assert_eq!(stats.median, Duration::from_micros(7));
+    assert_eq!(stats.p95, Duration::from_micros(7));
+}
+
+#[test]
+fn test_time_solve() {
+    use std::cell::Cell;
+
+    let parses = Cell::new(0);
+    let parse = |input: &str| {
+        parses.set(parses.get() + 1);
+        error::parse::<i32>(input, input)
+    };
+
+    time_solve("7", 5, parse, |n| Ok(n * 2)).unwrap();
+    assert_eq!(parses.get(), 1);
+    assert!(time_solve("x", 5, parse, |n| Ok(n * 2)).is_err());
+    assert!(time_solve("7", 5, parse, |_| error::Result::<i32>::Err(
+        error::AocError::new("no")
+    ))
+    .is_err());
+
+    let day = crate::registry::get(5).unwrap();
+    let solve = day.solve.unwrap();
+    assert!(solve(
+        crate::days::day05::TEST_INPUT,
+        &Params::defaults(&[]),
+        Part::B,
+        3
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_report() {
+    let timings = [Timing {
+        day: 17,
+        part: Part::A,
+        name: "day17::a".to_string(),
+        input: "17.txt".to_string(),
+        answer: "7,5,4".to_string(),
+        stats: Stats::from_samples(&mut [Duration::from_micros(2)]),
+        alloc: None,
+        spans: Vec::new(),
+    }];
+
+    assert_eq!(
+        report(&timings, Format::Csv),
+        "day,part,input,answer,runs,min_ns,median_ns,mean_ns,p95_ns\n17,a,17.txt,\"7,5,4\",1,2000,2000,2000,2000\n"
+    );
+    assert!(report(&timings, Format::Json).contains("\"answer\": \"7,5,4\", \"runs\": 1"));
+
+    let timings = [Timing {
+        spans: vec![SpanStats {
+            name: "parse",
+            calls: 1,
+            total: Duration::from_micros(1),
+        }],
+        ..timings.into_iter().next().unwrap()
+    }];
+
+    assert!(
+        report(&timings, Format::Table).contains("day17::a parse      1         1.0      50.0%")
+    );
+    assert!(report(&timings, Format::Json)
+        .contains("\"spans\": [{\"name\": \"parse\", \"calls\": 1, \"ns\": 1000}]"));
+    assert!(report(&timings, Format::Csv).ends_with(",2000,parse=1000\n"));
+}
+
+#[test]
+fn test_day_spans() {
+    let timing = |day, part, spans: &[(&'static str, u64)]| Timing {
+        day,
+        part,
+        name: format!("day{day:02}::{part}"),
+        input: format!("{day}.txt"),
+        answer: "1".to_string(),
+        stats: Stats::from_samples(&mut [Duration::from_micros(10)]),
+        alloc: None,
+        spans: spans
+            .iter()
+            .map(|&(name, us)| SpanStats {
+                name,
+                calls: 1,
+                total: Duration::from_micros(us),
+            })
+            .collect(),
+    };
+
+    let timings = [
+        timing(16, Part::A, &[("parse", 1), ("search", 8)]),
+        timing(5, Part::A, &[]),
+        timing(16, Part::B, &[("parse", 2), ("collect", 3)]),
+    ];
+
+    let days = day_spans(&timings);
+
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].day, 16);
+    assert_eq!(days[0].median, Duration::from_micros(20));
+    assert_eq!(
+        days[0]
+            .spans
+            .iter()
+            .map(|s| (s.name, s.calls, s.total.as_micros()))
+            .collect::<Vec<_>>(),
+        [("parse", 2, 3), ("search", 1, 8), ("collect", 1, 3)]
+    );
+
+    let table = report(&timings, Format::Table);
+    assert!(table.contains("day16 parse           2         3.0      15.0%"));
+    assert!(report(&timings, Format::Json).contains("\"day_spans\": [\n    {\"day\": 16, \"median_ns\": 20000, \"spans\": [{\"name\": \"parse\", \"calls\": 2, \"ns\": 3000}"));
+}